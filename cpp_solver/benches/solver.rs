@@ -0,0 +1,81 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+
+use cpp_solver::{bench, CppSolver, Graph, ImbalancedNodeSet};
+
+/// Returns a random strongly connected graph, the same for every run.
+fn random_graph(n_nodes: usize) -> Graph {
+    Graph::random_connected(n_nodes, 0.1, 1.0..10.0, 42)
+}
+
+fn solve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve");
+    for n_nodes in [10, 50, 100, 200] {
+        let graph = random_graph(n_nodes);
+        group.bench_with_input(BenchmarkId::from_parameter(n_nodes), &graph, |b, graph| {
+            b.iter(|| CppSolver::new(graph.clone()).solve().unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn floyd_warshall(c: &mut Criterion) {
+    let mut group = c.benchmark_group("floyd_warshall");
+    for n_nodes in [50, 100, 200, 400] {
+        let graph = random_graph(n_nodes);
+        group.bench_with_input(BenchmarkId::from_parameter(n_nodes), &graph, |b, graph| {
+            b.iter(|| bench::floyd_warshall(graph))
+        });
+    }
+    group.finish();
+}
+
+fn hungarian(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hungarian");
+    for n_imbalanced in [10, 50, 100, 200] {
+        let imbalanced_nodes = ImbalancedNodeSet {
+            negative: (0..n_imbalanced).collect(),
+            positive: (n_imbalanced..2 * n_imbalanced).collect(),
+        };
+        // Pseudo-random distances, so the matching is not trivial
+        let shortest_distance =
+            |from: usize, to: usize| ((from * 7919 + to * 104729) % 1000) as f64;
+        group.bench_with_input(
+            BenchmarkId::from_parameter(n_imbalanced),
+            &imbalanced_nodes,
+            |b, imbalanced_nodes| {
+                b.iter(|| bench::hungarian_match(black_box(imbalanced_nodes), shortest_distance))
+            },
+        );
+    }
+    group.finish();
+}
+
+fn hungarian_on_graph(c: &mut Criterion) {
+    let graph = random_graph(200);
+    let imbalanced_nodes = bench::imbalanced_nodes(&graph);
+    // Look the distances up beforehand, so only the matching is timed
+    let solver = CppSolver::new(graph.clone());
+    let labels = graph.node_labels();
+    let distances: Vec<Vec<f64>> = labels
+        .iter()
+        .map(|from| {
+            labels
+                .iter()
+                .map(|to| solver.shortest_distance(from, to).unwrap_or(f64::INFINITY))
+                .collect()
+        })
+        .collect();
+    c.bench_function("hungarian_on_graph/200", |b| {
+        b.iter(|| bench::hungarian_match(&imbalanced_nodes, |from, to| distances[from][to]))
+    });
+}
+
+criterion_group!(
+    benches,
+    solve,
+    floyd_warshall,
+    hungarian,
+    hungarian_on_graph
+);
+criterion_main!(benches);
@@ -0,0 +1,29 @@
+"""Type stubs for the `cpp_solver` Python module, built with the `python` feature."""
+
+from typing import TypedDict
+
+class SolveError(Exception):
+    """Raised when the graph has no postman route."""
+
+class Graph:
+    """A built graph, ready to be solved."""
+
+class GraphBuilder:
+    """Builds a graph from numbered or labeled edges."""
+
+    def __init__(self) -> None: ...
+    def add_edge(self, from_: int, to: int, weight: float, /) -> None:
+        """Adds a directed edge between two numbered nodes, or raises `ValueError` if the weight
+        is NaN or infinite."""
+    def add_labeled_edge(self, from_: str, to: str, weight: float, /) -> None:
+        """Adds a directed edge between two labeled nodes, or raises `ValueError` if the weight
+        is NaN or infinite."""
+    def build(self) -> Graph:
+        """Builds the graph from the edges added so far, which are kept for later builds."""
+
+class Route(TypedDict):
+    path: list[str]
+    cost: float
+
+def solve(graph: Graph, /) -> Route:
+    """Solves the Chinese Postman Problem, or raises `SolveError` if the graph has no route."""
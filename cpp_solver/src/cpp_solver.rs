@@ -1,151 +1,1277 @@
+/// Module for the entry points into single stages, used by the benchmarks.
+#[cfg(feature = "bench")]
+pub mod bench;
+
+/// Module for the Blossom algorithm implementation.
+mod blossom;
+
+/// Module for the Dijkstra algorithm implementation.
+mod dijkstra;
+
+/// Module for the shortest paths shared by `CppSolver::precompute`.
+mod distance_oracle;
+
 /// Module for the Floyd-Warshall algorithm implementation.
 mod floyd_warshall;
 
+/// Module for the greedy nearest-neighbour matching implementation.
+mod greedy;
+
 /// Module for the Hierholzer algorithm implementation.
 mod hierholzer;
 
 /// Module for the Hungarian algorithm implementation.
 mod hungarian;
 
-use ndarray::Array2;
+/// Module for the Johnson reweighting implementation.
+mod johnson;
+
+/// Module for selecting the matching algorithm.
+mod matching_strategy;
+
+/// Module for selecting the shortest path algorithm.
+mod shortest_path;
+
+/// Module for the solved route together with its figures.
+mod solution;
 
-use crate::{cpp_solver::hungarian::Matching, graph::Graph};
+/// Module for the errors returned by the solver.
+mod solve_error;
+
+/// Module for the hooks into the stages of a solve.
+mod solve_observer;
+
+/// Module for the options of the solver.
+mod solver_config;
+
+use log::{debug, info};
+use ndarray::ArrayView2;
+
+use crate::{
+    cpp_solver::hungarian::Matching,
+    graph::{Graph, GraphError, ImbalancedNodeSet},
+    Weight,
+};
+use dijkstra::DijkstraRunner;
+pub use distance_oracle::DistanceOracle;
 use floyd_warshall::FloydWarshallRunner;
 use hierholzer::HierholzerRunner;
-use std::{collections::VecDeque, fmt};
+pub use matching_strategy::MatchingStrategy;
+pub use shortest_path::ShortestPathStrategy;
+use shortest_path::ShortestPaths;
+pub use solution::Solution;
+pub use solve_error::SolveError;
+pub use solve_observer::SolveObserver;
+pub use solver_config::SolverConfig;
+use std::{
+    cell::OnceCell,
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::Arc,
+    time::Instant,
+};
+
+/// Checks if a deadline has passed. Without a deadline, it never does.
+fn is_past(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// The epsilon to pass to [`Path::cost_approx_eq`] for costs up to about a million.
+///
+/// Scale it with larger costs, as the rounding error of a sum grows with its size.
+pub const DEFAULT_COST_EPSILON: f64 = 1e-9;
 
 /// Represents a path in the graph.
-pub struct Path {
+#[derive(Clone)]
+pub struct Path<W = f64> {
     pub path: VecDeque<usize>,
-    pub cost: f64,
+    pub cost: W,
+    /// The part of `cost` spent walking edges again to balance the graph, i.e. deadheading.
+    pub balancing_cost: W,
+    weights: Vec<W>,
     labels: Vec<String>,
 }
 
 /// Solver for the Chinese Postman Problem.
-pub struct CppSolver {
-    graph: Graph,
-    floyd_warshall: FloydWarshallRunner,
-    hierholzer: HierholzerRunner,
+///
+/// Cloning a solver keeps the shortest paths it already computed, e.g. to try several options
+/// on the same graph.
+#[derive(Clone)]
+pub struct CppSolver<W = f64> {
+    graph: Graph<W>,
+    /// The number of edges of the given graph, before any balancing edges were added.
+    n_edges: usize,
+    /// The edges repeated by the last solve, as `(from, to)`.
+    balancing_edges: Vec<(usize, usize)>,
+    /// The shortest paths walked again by the last solve, one per matched pair of nodes.
+    balancing_routes: Vec<Vec<usize>>,
+    config: SolverConfig,
+    /// Shared with the oracles returned by `precompute` and with clones of the solver.
+    floyd_warshall: OnceCell<Arc<FloydWarshallRunner<W>>>,
+    potentials: OnceCell<Option<Vec<W>>>,
+    hierholzer: HierholzerRunner<W>,
 }
 
-impl CppSolver {
+impl<W: Weight> CppSolver<W> {
     /// Creates a new instance of the solver.
     ///
     /// # Arguments
     ///
     /// * `graph` - The graph to solve the problem on.
-    pub fn new(graph: Graph) -> Self {
+    pub fn new(graph: Graph<W>) -> Self {
+        Self::with_config(graph, SolverConfig::default())
+    }
+
+    /// Creates a new instance of the solver using the given shortest path algorithm.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to solve the problem on.
+    /// * `strategy` - How shortest distances between nodes are computed.
+    pub fn with_strategy(graph: Graph<W>, strategy: ShortestPathStrategy) -> Self {
+        Self::with_config(
+            graph,
+            SolverConfig {
+                strategy,
+                ..SolverConfig::default()
+            },
+        )
+    }
+
+    /// Creates a new instance of the solver with the given options.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to solve the problem on.
+    /// * `config` - The options of the solver.
+    pub fn with_config(graph: Graph<W>, config: SolverConfig) -> Self {
         Self {
-            floyd_warshall: FloydWarshallRunner::new(graph.weight_matrix().clone()),
-            hierholzer: HierholzerRunner::new(),
+            n_edges: graph.edge_count(),
+            balancing_edges: Vec::new(),
+            balancing_routes: Vec::new(),
             graph,
+            config,
+            floyd_warshall: OnceCell::new(),
+            potentials: OnceCell::new(),
+            hierholzer: HierholzerRunner::with_canonical_order(config.canonical_output),
+        }
+    }
+
+    /// Computes the all-pairs shortest paths now, instead of on first use.
+    ///
+    /// Every later solve, distance query and clone of the solver reuses them, e.g. to try every
+    /// start node or several configs with `set_config` for a "best depot" analysis without
+    /// running Floyd-Warshall again. Solves only use them with the Floyd-Warshall strategy.
+    ///
+    /// # Returns
+    ///
+    /// The shortest paths, shared with the solver rather than copied, which can be kept while
+    /// solving. Edges added later with `add_edge` update the solver's shortest paths only.
+    pub fn precompute(&self) -> DistanceOracle<W> {
+        DistanceOracle {
+            runner: Arc::clone(self.floyd_warshall_shared()),
+        }
+    }
+
+    /// Replaces the options of the solver, keeping the shortest paths computed so far.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The options of the next solves.
+    pub fn set_config(&mut self, config: SolverConfig) {
+        self.config = config;
+        self.hierholzer = HierholzerRunner::with_canonical_order(config.canonical_output);
+    }
+
+    /// Solves the Chinese Postman Problem and returns the optimal path, starting at the
+    /// configured start node.
+    ///
+    /// # Returns
+    ///
+    /// The optimal path if the graph is solvable, or the reason it is not. A graph without nodes
+    /// has no route, and yields `SolveError::EmptyGraph`.
+    pub fn solve(&mut self) -> Result<Path<W>, SolveError> {
+        self.solve_from(self.config.start)
+    }
+
+    /// Solves the Chinese Postman Problem like `solve`, along with the figures of the route.
+    ///
+    /// # Returns
+    ///
+    /// The optimal path with its costs, length and start node if the graph is solvable, or the
+    /// reason it is not.
+    pub fn solve_detailed(&mut self) -> Result<Solution<W>, SolveError> {
+        self.solve().map(Solution::new)
+    }
+
+    /// Solves the Chinese Postman Problem and returns the optimal path starting at a given node.
+    ///
+    /// The route is a circuit, so every start node yields the same cost.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The node the route starts and ends at, e.g. a depot.
+    ///
+    /// # Returns
+    ///
+    /// The optimal path if the graph is solvable, or the reason it is not.
+    pub fn solve_from(&mut self, start: usize) -> Result<Path<W>, SolveError> {
+        self.solve_observed(start, &mut ())
+    }
+
+    /// Solves the Chinese Postman Problem like `solve`, reporting each stage to an observer.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - Receives the imbalanced nodes, the chosen matching, each balancing path
+    ///   and the final route. Undirected solves only report the final route.
+    ///
+    /// # Returns
+    ///
+    /// The optimal path if the graph is solvable, or the reason it is not.
+    pub fn solve_with_observer(
+        &mut self,
+        observer: &mut impl SolveObserver<W>,
+    ) -> Result<Path<W>, SolveError> {
+        self.solve_observed(self.config.start, observer)
+    }
+
+    /// Solves the problem from a given start node, reporting each stage to an observer.
+    fn solve_observed(
+        &mut self,
+        start: usize,
+        observer: &mut impl SolveObserver<W>,
+    ) -> Result<Path<W>, SolveError> {
+        if self.config.undirected {
+            return self.solve_undirected_observed(start, observer);
+        }
+        self.reset();
+        let deadline = self.deadline();
+        let open_start = self.open_route_start();
+        self.check_solvable(open_start.is_some() || self.graph.is_balanced(), deadline)?;
+        self.check_start(start)?;
+        info!("The graph is solvable. Proceeding with the solution.");
+
+        if let Some(open_start) = open_start {
+            debug!("The graph is semi-Eulerian, so the route is left open.");
+            self.hierholzer.run(&self.graph, open_start)?;
+            let path = Path::new(
+                self.hierholzer.path(),
+                self.hierholzer.weights(),
+                self.graph.node_labels(),
+            )?;
+            self.check_traversals(&path)?;
+            observer.on_euler_path(&path.path);
+            return Ok(path);
+        }
+
+        let balancing_cost = self.balance_node(observer, deadline)?;
+        if !self.graph.is_balanced() {
+            return Err(SolveError::NotEulerianAfterBalancing {
+                uncovered: self.graph.unbalanced_edges(),
+            });
+        }
+        self.hierholzer.run(&self.graph, start)?;
+
+        let path = Path {
+            balancing_cost,
+            ..Path::new(
+                self.hierholzer.path(),
+                self.hierholzer.weights(),
+                self.graph.node_labels(),
+            )?
+        };
+        self.check_traversals(&path)?;
+        observer.on_euler_path(&path.path);
+        Ok(path)
+    }
+
+    /// Solves the undirected Chinese Postman Problem and returns the optimal path, starting at
+    /// the configured start node.
+    ///
+    /// Every street must be stored in both directions with the same weight, and is walked in
+    /// only one of them. Odd-degree nodes are paired with a minimum-weight perfect matching.
+    ///
+    /// # Returns
+    ///
+    /// The optimal path if the graph is solvable, or the reason it is not.
+    pub fn solve_undirected(&mut self) -> Result<Path<W>, SolveError> {
+        self.solve_undirected_observed(self.config.start, &mut ())
+    }
+
+    /// Solves the undirected problem from a given start node, reporting the route to an observer.
+    fn solve_undirected_observed(
+        &mut self,
+        start: usize,
+        observer: &mut impl SolveObserver<W>,
+    ) -> Result<Path<W>, SolveError> {
+        self.reset();
+        if !self.graph.is_symmetric() {
+            return Err(SolveError::NotUndirected);
+        }
+        let deadline = self.deadline();
+        self.check_solvable(self.graph.odd_degree_nodes().is_empty(), deadline)?;
+        self.check_start(start)?;
+        info!("The graph is solvable. Proceeding with the solution.");
+
+        let balancing_cost = self.balance_odd_nodes(deadline)?;
+        if !self.graph.odd_degree_nodes().is_empty() {
+            return Err(SolveError::NotEulerianAfterBalancing {
+                uncovered: self.graph.odd_degree_edges(),
+            });
+        }
+        self.hierholzer.run_undirected(&self.graph, start)?;
+
+        let path = Path {
+            balancing_cost,
+            ..Path::new(
+                self.hierholzer.path(),
+                self.hierholzer.weights(),
+                self.graph.node_labels(),
+            )?
+        };
+        observer.on_euler_path(&path.path);
+        Ok(path)
+    }
+
+    /// Removes the edges added while balancing, so the solver holds the graph it was given again.
+    ///
+    /// Every solve starts with a reset, so solving twice yields the same route and cost. The
+    /// balancing edges only repeat existing edges, so cached shortest paths stay valid.
+    pub fn reset(&mut self) {
+        self.graph.truncate_edges(self.n_edges);
+        self.balancing_edges.clear();
+        self.balancing_routes.clear();
+    }
+
+    /// Returns the edges the last solve walks a second time to balance the graph.
+    ///
+    /// An edge repeated several times is listed once per repetition. After an undirected solve,
+    /// each repeated street is listed once, in the direction of the shortest path that chose it.
+    ///
+    /// # Returns
+    ///
+    /// The `(from, to)` labels of each repeated edge, in the order balancing added them.
+    pub fn balancing_edges(&self) -> Vec<(String, String)> {
+        let labels = self.graph.node_labels();
+        self.balancing_edges
+            .iter()
+            .map(|&(from, to)| (labels[from].clone(), labels[to].clone()))
+            .collect()
+    }
+
+    /// Returns the routes the last solve walks a second time to balance the graph, e.g. to tell
+    /// a worker which streets to deadhead along.
+    ///
+    /// Each route is the shortest path between one matched pair of nodes, so together they hold
+    /// the same edges as `balancing_edges`.
+    ///
+    /// # Returns
+    ///
+    /// The labels of the nodes along each route, in the order balancing added them.
+    pub fn balancing_routes(&self) -> Vec<Vec<String>> {
+        let labels = self.graph.node_labels();
+        self.balancing_routes
+            .iter()
+            .map(|route| route.iter().map(|&node| labels[node].clone()).collect())
+            .collect()
+    }
+
+    /// Adds an edge to the graph, keeping the shortest paths computed so far up to date.
+    ///
+    /// The Floyd-Warshall distances are relaxed through the new edge in quadratic time instead of
+    /// being recomputed in cubic time. Edges added by a previous solve are removed first.
+    ///
+    /// # Panics
+    ///
+    /// If either node is not in the graph.
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: W) {
+        self.reset();
+        self.graph.add_edge(from, to, weight);
+        self.n_edges += 1;
+        if let Some(floyd_warshall) = self.floyd_warshall.get_mut() {
+            Arc::make_mut(floyd_warshall).add_edge(from, to, weight);
+        }
+        self.potentials = OnceCell::new();
+    }
+
+    /// Solves each strongly connected component of the graph on its own, e.g. separate districts.
+    ///
+    /// Edges between components can't be part of any route, so they are left out. A component
+    /// without edges, such as an isolated node, gets an empty route. Each route starts at the
+    /// configured start node if it is in the component, and at its smallest node otherwise.
+    ///
+    /// # Returns
+    ///
+    /// The route of each component, or the reason it has none, ordered by the smallest node of
+    /// the component. Node indices and labels refer to the whole graph.
+    pub fn solve_components(&mut self) -> Vec<Result<Path<W>, SolveError>> {
+        self.reset();
+        self.graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|nodes| self.solve_component(&nodes))
+            .collect()
+    }
+
+    /// Solves the subgraph induced by the nodes of a strongly connected component.
+    fn solve_component(&self, nodes: &[usize]) -> Result<Path<W>, SolveError> {
+        let labels = self.graph.node_labels();
+        let subgraph = self.graph.subgraph(nodes);
+        if subgraph.edge_count() == 0 {
+            return Path::new(VecDeque::new(), Vec::new(), labels);
+        }
+
+        let config = SolverConfig {
+            start: nodes
+                .iter()
+                .position(|&node| node == self.config.start)
+                .unwrap_or(0),
+            ..self.config
+        };
+        let path = CppSolver::with_config(subgraph, config).solve()?;
+        Ok(Path {
+            path: path.path.iter().map(|&node| nodes[node]).collect(),
+            labels: labels.to_vec(),
+            ..path
+        })
+    }
+
+    /// Finds the cheapest candidate edge whose addition makes the graph solvable.
+    ///
+    /// Each candidate is checked against the already computed shortest distances, so the
+    /// graph is never modified and no candidate requires a new Floyd-Warshall run.
+    ///
+    /// # Arguments
+    ///
+    /// * `candidates` - The edges that could be added, as `(from, to, weight)`.
+    ///
+    /// # Returns
+    ///
+    /// The cheapest candidate that makes the graph strongly connected without introducing a
+    /// negative cycle, or `None` if no candidate does.
+    pub fn cheapest_connecting_edge(
+        &self,
+        candidates: &[(usize, usize, W)],
+    ) -> Option<(usize, usize, W)> {
+        candidates
+            .iter()
+            .filter(|&&(from, to, weight)| {
+                self.floyd_warshall()
+                    .graph_is_strongly_connected_with(from, to)
+                    && self
+                        .floyd_warshall()
+                        .graph_has_no_negative_cycle_with(from, to, weight)
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .copied()
+    }
+
+    /// Returns the longest shortest distance from a node to any other node.
+    ///
+    /// Reuses the Floyd-Warshall distances, computing them on first use.
+    ///
+    /// # Returns
+    ///
+    /// The eccentricity of the node, or `None` if the node doesn't exist or can't reach every
+    /// other node.
+    pub fn eccentricity(&self, node: usize) -> Option<W> {
+        if node >= self.graph.node_count() {
+            return None;
+        }
+        let distances = self.floyd_warshall().shortest_distances();
+        let distances = distances
+            .row(node)
+            .iter()
+            .enumerate()
+            .filter(|&(to, _)| to != node)
+            .map(|(_, &distance)| distance)
+            .collect::<Option<Vec<_>>>()?;
+        Some(
+            distances
+                .into_iter()
+                .max_by(W::total_cmp)
+                .unwrap_or(W::zero()),
+        )
+    }
+
+    /// Returns the longest shortest distance between any two nodes.
+    ///
+    /// # Returns
+    ///
+    /// The largest eccentricity of all nodes, or `None` if the graph is empty or not strongly
+    /// connected.
+    pub fn diameter(&self) -> Option<W> {
+        (0..self.graph.node_count())
+            .map(|node| self.eccentricity(node))
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .max_by(W::total_cmp)
+    }
+
+    /// Estimates the cost of the route without finding the optimal matching, e.g. to show a
+    /// range to the user before solving a huge graph.
+    ///
+    /// The lower bound walks every edge once. The upper bound adds the balancing paths of a
+    /// greedy nearest-neighbour matching, which is never cheaper than the optimal one. Edges
+    /// added by a previous solve are removed first.
+    ///
+    /// # Returns
+    ///
+    /// The `(lower, upper)` bounds of the cost of `solve`, or the reason the graph is not
+    /// solvable. With negative weights, balancing may cost less than nothing, so the optimal
+    /// cost can fall below the lower bound.
+    pub fn estimate_bounds(&mut self) -> Result<(W, W), SolveError> {
+        self.reset();
+        let imbalanced_nodes = self.graph.imbalanced_nodes();
+        self.check_solvable(imbalanced_nodes.is_empty(), None)?;
+        let required_cost = self.graph.required_edge_cost();
+        if imbalanced_nodes.is_empty() {
+            return Ok((required_cost, required_cost));
+        }
+
+        let shortest_paths =
+            self.shortest_paths(&imbalanced_nodes.negative, &imbalanced_nodes.positive, None)?;
+        let mut upper_bound = required_cost;
+        let matching = greedy::nearest_match(&imbalanced_nodes, |from, to| {
+            shortest_paths
+                .shortest_distance(from, to)
+                .map_or(f64::INFINITY, W::to_f64)
+        });
+        for Matching { from, to } in matching {
+            let cost = shortest_paths
+                .shortest_distance(from, to)
+                .ok_or(SolveError::DistanceOverflow)?;
+            upper_bound = upper_bound + cost;
+        }
+        Ok((required_cost, upper_bound))
+    }
+
+    /// Looks up the shortest distance between two labeled nodes.
+    ///
+    /// Reuses the Floyd-Warshall distances, computing them on first use. A node is at distance
+    /// zero from itself.
+    ///
+    /// # Returns
+    ///
+    /// The shortest distance, or `None` if either label is unknown or `to` is unreachable.
+    pub fn shortest_distance(&self, from: &str, to: &str) -> Option<W> {
+        let from = self.graph.index_of_label(from)?;
+        let to = self.graph.index_of_label(to)?;
+        if from == to {
+            return Some(W::zero());
         }
+        self.floyd_warshall().shortest_distances()[(from, to)]
     }
 
-    /// Solves the Chinese Postman Problem and returns the optimal path.
+    /// Reconstructs a shortest path between two nodes from the successor matrix.
+    ///
+    /// Reuses the Floyd-Warshall paths, computing them on first use. A path from a node to
+    /// itself is just that node.
     ///
     /// # Returns
     ///
-    /// An `Option` containing the optimal path if the graph is solvable, or `None` otherwise.
-    pub fn solve(&mut self) -> Option<Path> {
-        if !self.solvable() {
-            println!("The graph is not solvable.");
+    /// The nodes along the path, including both ends, or `None` if either node doesn't exist or
+    /// `to` is unreachable.
+    pub fn shortest_path_between(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let n_nodes = self.graph.node_count();
+        if from >= n_nodes || to >= n_nodes {
             return None;
         }
-        println!("The graph is solvable. Proceeding with the solution.");
+        if from == to {
+            return Some(vec![from]);
+        }
+        Some(self.floyd_warshall().shortest_path_between(from, to)).filter(|path| !path.is_empty())
+    }
 
-        self.balance_node();
-        self.hierholzer.run(&self.graph);
+    /// Looks up the shortest route between two labeled nodes.
+    ///
+    /// # Returns
+    ///
+    /// The labels of the nodes along the route, including both ends, or an empty vector if either
+    /// label is unknown or `to` is unreachable.
+    pub fn shortest_route(&self, from: &str, to: &str) -> Vec<String> {
+        let (Some(from), Some(to)) = (
+            self.graph.index_of_label(from),
+            self.graph.index_of_label(to),
+        ) else {
+            return Vec::new();
+        };
+        self.floyd_warshall()
+            .shortest_path_between(from, to)
+            .into_iter()
+            .map(|node| self.graph.node_labels()[node].clone())
+            .collect()
+    }
 
-        Some(Path::new(
-            self.hierholzer.path(),
-            self.graph.weight_matrix(),
-            self.graph.node_labels(),
-        ))
+    /// Returns the Floyd-Warshall successor matrix of the graph.
+    ///
+    /// `next[(i, j)]` is the first hop on a shortest path from `i` to `j`, or `None` if `j` is
+    /// unreachable from `i`. The matrix is computed on first use if the solver's strategy
+    /// did not already need it.
+    pub fn next_matrix(&self) -> ArrayView2<'_, Option<usize>> {
+        self.floyd_warshall().next_matrix()
+    }
+
+    /// Returns the all-pairs shortest paths, running Floyd-Warshall on first use.
+    ///
+    /// The runner only reads the weight matrix of the graph, which it doesn't copy.
+    fn floyd_warshall(&self) -> &FloydWarshallRunner<W> {
+        self.floyd_warshall_shared()
+    }
+
+    /// Returns the all-pairs shortest paths like `floyd_warshall`, as shared with the oracles.
+    fn floyd_warshall_shared(&self) -> &Arc<FloydWarshallRunner<W>> {
+        self.floyd_warshall
+            .get_or_init(|| Arc::new(FloydWarshallRunner::new(self.graph.weight_matrix().view())))
+    }
+
+    /// Returns the all-pairs shortest paths like `floyd_warshall`, unless running Floyd-Warshall
+    /// takes past the deadline, in which case nothing is kept for the next call.
+    fn floyd_warshall_until(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<&FloydWarshallRunner<W>, SolveError> {
+        if let Some(runner) = self.floyd_warshall.get() {
+            return Ok(runner);
+        }
+        let runner =
+            FloydWarshallRunner::with_deadline(self.graph.weight_matrix().view(), deadline)
+                .ok_or(SolveError::TimedOut)?;
+        Ok(self.floyd_warshall.get_or_init(|| Arc::new(runner)))
+    }
+
+    /// Returns the time a solve started now must finish by, if the config sets a timeout.
+    fn deadline(&self) -> Option<Instant> {
+        self.config.timeout.map(|timeout| Instant::now() + timeout)
+    }
+
+    /// Returns the Johnson potentials, running Bellman-Ford on first use.
+    ///
+    /// `None` means the graph has a negative cycle.
+    fn potentials(&self) -> Option<&[W]> {
+        self.potentials
+            .get_or_init(|| johnson::potentials(&self.graph))
+            .as_deref()
+    }
+
+    /// Prepares the shortest paths from `sources` to `targets` using the solver's strategy.
+    ///
+    /// # Returns
+    ///
+    /// The shortest paths, or `SolveError::TimedOut` if the deadline passed while finding them.
+    fn shortest_paths(
+        &self,
+        sources: &[usize],
+        targets: &[usize],
+        deadline: Option<Instant>,
+    ) -> Result<ShortestPaths<'_, W>, SolveError> {
+        let shortest_paths = match self.config.strategy {
+            ShortestPathStrategy::FloydWarshall => {
+                ShortestPaths::FloydWarshall(self.floyd_warshall_until(deadline)?)
+            }
+            ShortestPathStrategy::Dijkstra => {
+                ShortestPaths::Dijkstra(DijkstraRunner::new(&self.graph, sources, targets))
+            }
+            ShortestPathStrategy::Johnson => {
+                let potentials = self
+                    .potentials()
+                    .expect("checked for negative cycles before balancing");
+                ShortestPaths::Dijkstra(DijkstraRunner::with_potentials(
+                    &self.graph,
+                    sources,
+                    targets,
+                    potentials,
+                ))
+            }
+        };
+        if is_past(deadline) {
+            return Err(SolveError::TimedOut);
+        }
+        Ok(shortest_paths)
     }
 
     /// Checks if the graph is solvable.
     ///
+    /// # Arguments
+    ///
+    /// * `balanced` - Whether the graph is already Eulerian, so no shortest paths are needed.
+    /// * `deadline` - The time by which Floyd-Warshall, if needed, must have finished.
+    ///
     /// # Returns
     ///
-    /// `true` if the graph is solvable, `false` otherwise.
-    fn solvable(&self) -> bool {
-        let connected = self.floyd_warshall.graph_is_strongly_connected();
-        let has_no_negative_cycle = self.floyd_warshall.graph_has_no_negative_cycle();
+    /// `Ok(())` if the graph is solvable, or the reason it is not.
+    fn check_solvable(&self, balanced: bool, deadline: Option<Instant>) -> Result<(), SolveError> {
+        if self.graph.weight_matrix().is_empty() {
+            return Err(SolveError::EmptyGraph);
+        }
+        // Reachability is searched on the edges alone, so huge or overflowing distances can't
+        // be mistaken for missing ones.
+        if !self.graph.is_strongly_connected() {
+            return Err(SolveError::NotStronglyConnected(
+                self.graph.strongly_connected_components(),
+            ));
+        }
+        match self.config.strategy {
+            ShortestPathStrategy::FloydWarshall if !balanced => {
+                if !self
+                    .floyd_warshall_until(deadline)?
+                    .graph_has_no_negative_cycle()
+                {
+                    return Err(SolveError::NegativeCycle);
+                }
+            }
+            ShortestPathStrategy::Dijkstra => {
+                if self.graph.has_negative_weights() {
+                    return Err(SolveError::NegativeWeight);
+                }
+            }
+            // A balanced graph skips Floyd-Warshall, and only runs Bellman-Ford to look for
+            // negative cycles if some weight is negative.
+            ShortestPathStrategy::FloydWarshall | ShortestPathStrategy::Johnson => {
+                if self.graph.has_negative_weights() && self.potentials().is_none() {
+                    return Err(SolveError::NegativeCycle);
+                }
+            }
+        }
+        Ok(())
+    }
 
-        println!(
-            "The graph is {}strongly connected.",
-            if connected { "" } else { "not " }
-        );
-        println!(
-            "The graph has {} negative cycles.",
-            if has_no_negative_cycle { "no" } else { "" }
-        );
+    /// Returns the node an open route starts at, if open routes are allowed and the graph is
+    /// semi-Eulerian.
+    fn open_route_start(&self) -> Option<usize> {
+        if !self.config.open {
+            return None;
+        }
+        match self.graph.imbalanced_nodes() {
+            ImbalancedNodeSet { negative, positive }
+                if negative.len() == 1 && positive.len() == 1 =>
+            {
+                Some(positive[0])
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks if a route can start at the given node.
+    fn check_start(&self, start: usize) -> Result<(), SolveError> {
+        match self.graph.out_degrees().get(start) {
+            Some(&out_degree) if out_degree > 0 => Ok(()),
+            _ => Err(SolveError::InvalidStartNode(start)),
+        }
+    }
+
+    /// Checks that a directed route walks no edge more often than the configured limit.
+    ///
+    /// The limit of each pair of nodes is shared by the parallel edges given between them.
+    fn check_traversals(&self, path: &Path<W>) -> Result<(), SolveError> {
+        let Some(max_traversals) = self.config.max_traversals else {
+            return Ok(());
+        };
+        let mut n_parallel = HashMap::new();
+        for (from, tos) in self.graph.edge_set().into_iter().enumerate() {
+            for to in tos {
+                *n_parallel.entry((from, to)).or_insert(0) += 1;
+            }
+        }
+        for edge in &self.balancing_edges {
+            *n_parallel
+                .get_mut(edge)
+                .expect("balancing edges are in the graph") -= 1;
+        }
+
+        let mut violations: Vec<_> = path
+            .edge_traversal_counts()
+            .into_iter()
+            .filter(|(edge, traversals)| *traversals > max_traversals * n_parallel[edge])
+            .collect();
+        violations.sort_unstable();
+        match violations.first() {
+            Some(&((from, to), traversals)) => Err(SolveError::ConstraintViolation {
+                from,
+                to,
+                traversals,
+            }),
+            None => Ok(()),
+        }
+    }
 
-        connected && has_no_negative_cycle
+    /// Returns the cheapest weight of the edge between two nodes on a shortest path.
+    fn edge_weight(&self, from: usize, to: usize) -> W {
+        self.graph.weight_matrix()[[from, to]].expect("shortest paths only follow existing edges")
     }
 
-    /// Balances the imbalanced nodes in the graph using the Hungarian algorithm.
-    fn balance_node(&mut self) {
+    /// Balances the imbalanced nodes in the graph using the configured matching strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - Receives the imbalanced nodes, the chosen matching and each balancing path.
+    /// * `deadline` - The time by which the shortest paths and the matching must have finished.
+    ///
+    /// # Returns
+    ///
+    /// The total weight of the added edges, `SolveError::DistanceOverflow` if some pair of nodes
+    /// is too far apart to measure, or `SolveError::TimedOut` if the deadline passed.
+    fn balance_node(
+        &mut self,
+        observer: &mut impl SolveObserver<W>,
+        deadline: Option<Instant>,
+    ) -> Result<W, SolveError> {
         let imbalanced_nodes = self.graph.imbalanced_nodes();
         if imbalanced_nodes.is_empty() {
-            println!("The graph is already balanced.");
-            return;
+            debug!("The graph is already balanced.");
+            return Ok(W::zero());
+        }
+        observer.on_imbalance(&imbalanced_nodes);
+
+        debug!(
+            "Balancing imbalanced nodes using {:?} matching.",
+            self.config.matching
+        );
+        let paths: Vec<Vec<usize>> = {
+            let shortest_paths = self.shortest_paths(
+                &imbalanced_nodes.negative,
+                &imbalanced_nodes.positive,
+                deadline,
+            )?;
+            let shortest_distance = |from, to| {
+                shortest_paths
+                    .shortest_distance(from, to)
+                    .map_or(f64::INFINITY, W::to_f64)
+            };
+            let matching = match self.config.matching {
+                MatchingStrategy::Hungarian => {
+                    hungarian::best_match(&imbalanced_nodes, shortest_distance, deadline)
+                        .ok_or(SolveError::TimedOut)?
+                }
+                MatchingStrategy::Greedy => {
+                    greedy::nearest_match(&imbalanced_nodes, shortest_distance)
+                }
+            };
+            // Kuhn-Munkres can't be interrupted, so a late matching is only caught here
+            if is_past(deadline) {
+                return Err(SolveError::TimedOut);
+            }
+            matching
+                .into_iter()
+                .map(|Matching { from, to }| {
+                    let cost = shortest_paths
+                        .shortest_distance(from, to)
+                        .ok_or(SolveError::DistanceOverflow)?;
+                    observer.on_match(from, to, cost);
+                    Ok(shortest_paths.shortest_path_between(from, to))
+                })
+                .collect::<Result<_, _>>()?
+        };
+
+        let mut balancing_cost = W::zero();
+        for path in paths {
+            observer.on_balancing_path(&path);
+            for (i, &node) in path.iter().enumerate().skip(1) {
+                let prev = path[i - 1];
+                let weight = self.edge_weight(prev, node);
+                self.graph.add_edge(prev, node, weight);
+                self.balancing_edges.push((prev, node));
+                balancing_cost = balancing_cost
+                    .checked_add(weight)
+                    .ok_or(SolveError::DistanceOverflow)?;
+            }
+            self.balancing_routes.push(path);
         }
+        Ok(balancing_cost)
+    }
 
-        println!("Balancing imbalanced nodes using the Hungarian algorithm.");
-        let shortest_distance_between_nodes = self.floyd_warshall.shortest_distances();
-        let best_match = hungarian::best_match(&imbalanced_nodes, shortest_distance_between_nodes);
+    /// Pairs the odd-degree nodes using the Blossom algorithm and duplicates the streets between them.
+    ///
+    /// `check_solvable` has already rejected disconnected graphs, so every pair of odd nodes is
+    /// reachable and none enters the matching as an infinite distance.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - The time by which the shortest paths and the matching must have finished.
+    ///
+    /// # Returns
+    ///
+    /// The total weight of the added streets, each counted once,
+    /// `SolveError::DistanceOverflow` if some pair of nodes is too far apart to measure, or
+    /// `SolveError::TimedOut` if the deadline passed.
+    fn balance_odd_nodes(&mut self, deadline: Option<Instant>) -> Result<W, SolveError> {
+        let odd_nodes = self.graph.odd_degree_nodes();
+        if odd_nodes.is_empty() {
+            debug!("The graph is already balanced.");
+            return Ok(W::zero());
+        }
 
-        for Matching { from, to } in best_match {
-            let path = self.floyd_warshall.shortest_path_between(from, to);
+        debug!("Balancing odd-degree nodes using the Blossom algorithm.");
+        let paths: Vec<Vec<usize>> = {
+            let shortest_paths = self.shortest_paths(&odd_nodes, &odd_nodes, deadline)?;
+            let matching = blossom::best_match(
+                &odd_nodes,
+                |from, to| {
+                    shortest_paths
+                        .shortest_distance(from, to)
+                        .map_or(f64::INFINITY, W::to_f64)
+                },
+                deadline,
+            )
+            .ok_or(SolveError::TimedOut)?;
+            if is_past(deadline) {
+                return Err(SolveError::TimedOut);
+            }
+            matching
+                .into_iter()
+                .map(|Matching { from, to }| {
+                    shortest_paths
+                        .shortest_distance(from, to)
+                        .ok_or(SolveError::DistanceOverflow)?;
+                    Ok(shortest_paths.shortest_path_between(from, to))
+                })
+                .collect::<Result<_, _>>()?
+        };
 
+        let mut balancing_cost = W::zero();
+        for path in paths {
             for (i, &node) in path.iter().enumerate().skip(1) {
                 let prev = path[i - 1];
-                self.graph
-                    .add_edge(prev, node, self.graph.weight_matrix()[[prev, node]]);
+                let weight = self.edge_weight(prev, node);
+                self.graph.add_edge(prev, node, weight);
+                self.graph.add_edge(node, prev, weight);
+                self.balancing_edges.push((prev, node));
+                balancing_cost = balancing_cost
+                    .checked_add(weight)
+                    .ok_or(SolveError::DistanceOverflow)?;
             }
+            self.balancing_routes.push(path);
         }
+        Ok(balancing_cost)
     }
 }
 
-impl Path {
+impl<W: Weight> Path<W> {
     /// Creates a new instance of `Path`.
     ///
     /// # Arguments
     ///
     /// * `path` - The path as a sequence of node indices.
-    /// * `weight_matrix` - The weight matrix of the graph.
+    /// * `weights` - The weight of the edge walked between each pair of consecutive nodes.
     /// * `labels` - The labels of the nodes in the graph.
+    ///
+    /// # Returns
+    ///
+    /// The path, or `SolveError::DistanceOverflow` if its cost doesn't fit in the weight type.
     pub(crate) fn new(
         path: VecDeque<usize>,
-        weight_matrix: &Array2<f64>,
+        weights: Vec<W>,
         labels: &[String],
-    ) -> Self {
-        let cost = path
-            .iter()
-            .zip(path.iter().skip(1))
-            .map(|(from, to)| weight_matrix[(*from, *to)])
-            .sum();
-        Self {
+    ) -> Result<Self, SolveError> {
+        Ok(Self {
             path,
-            cost,
+            cost: W::checked_sum(weights.iter().copied()).ok_or(SolveError::DistanceOverflow)?,
+            balancing_cost: W::zero(),
+            weights,
             labels: labels.to_vec(),
-        }
+        })
     }
-}
 
-impl fmt::Display for Path {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let path = &self.labels[self.path[0]];
-        let path = self
+    /// Returns the labels of the nodes along the path, in order.
+    pub fn nodes(&self) -> Vec<String> {
+        self.path
+            .iter()
+            .map(|&node| self.labels[node].clone())
+            .collect()
+    }
+
+    /// Returns the position of each step of the path with the label of the node it reaches, e.g.
+    /// to animate the route.
+    ///
+    /// A node visited several times appears once per visit, each with its own position.
+    pub fn visit_sequence(&self) -> Vec<(usize, String)> {
+        self.path
+            .iter()
+            .map(|&node| self.labels[node].clone())
+            .enumerate()
+            .collect()
+    }
+
+    /// Joins the labels of the nodes along the path with a separator.
+    ///
+    /// Use `nodes` to render the path in another way.
+    ///
+    /// # Arguments
+    ///
+    /// * `separator` - The text between two labels, e.g. `","` for CSV.
+    pub fn format(&self, separator: &str) -> String {
+        self.nodes().join(separator)
+    }
+
+    /// Returns the edges walked by the path, in order.
+    ///
+    /// Use `iter_edges` to stream them without copying the labels.
+    ///
+    /// # Returns
+    ///
+    /// A `(from_label, to_label, weight)` triple per step. The weights sum to `cost`.
+    pub fn edges(&self) -> Vec<(String, String, W)> {
+        self.iter_edges()
+            .map(|(from, to, weight)| (from.to_string(), to.to_string(), weight))
+            .collect()
+    }
+
+    /// Iterates over the edges walked by the path, in order, borrowing the labels.
+    ///
+    /// # Returns
+    ///
+    /// An iterator of one `(from_label, to_label, weight)` triple per step.
+    pub fn iter_edges(&self) -> impl Iterator<Item = (&str, &str, W)> + '_ {
+        self.path
+            .iter()
+            .zip(self.path.iter().skip(1))
+            .zip(&self.weights)
+            .map(|((&from, &to), &weight)| {
+                (self.labels[from].as_str(), self.labels[to].as_str(), weight)
+            })
+    }
+
+    /// Checks if the cost of the path is within `epsilon` of another cost.
+    ///
+    /// Costs of fractional weights depend on the order they are summed in, so compare them with
+    /// this rather than `==`, usually with [`DEFAULT_COST_EPSILON`].
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The expected cost.
+    /// * `epsilon` - The largest difference still counted as equal.
+    pub fn cost_approx_eq(&self, other: f64, epsilon: f64) -> bool {
+        (self.cost.to_f64() - other).abs() <= epsilon
+    }
+
+    /// Returns the distance travelled when reaching each node of the path, for turn-by-turn output.
+    ///
+    /// The first entry is zero, and there is one entry per node in `path`. The totals are summed
+    /// step by step, so on long routes with fractional weights the last one may differ from
+    /// `cost` in the last digits.
+    pub fn cumulative_costs(&self) -> Vec<W> {
+        if self.path.is_empty() {
+            return Vec::new();
+        }
+        std::iter::once(W::zero())
+            .chain(self.weights.iter().scan(W::zero(), |total, &weight| {
+                *total = *total + weight;
+                Some(*total)
+            }))
+            .collect()
+    }
+
+    /// Prices the same route under other weights, e.g. rush-hour travel times, without solving
+    /// again.
+    ///
+    /// Each step is priced at the weight of the edge between its nodes, so of several parallel
+    /// edges the cheapest one is used.
+    ///
+    /// # Arguments
+    ///
+    /// * `weight_matrix` - The weight of the edge between each pair of nodes, or `None` if there
+    ///   is none, like `Graph::weight_matrix`.
+    ///
+    /// # Returns
+    ///
+    /// The cost of the route, or `None` if it walks an edge the matrix doesn't have or the cost
+    /// doesn't fit in the weight type.
+    pub fn recost(&self, weight_matrix: ArrayView2<Option<W>>) -> Option<W> {
+        let weights = self
             .path
             .iter()
-            .skip(1)
-            .fold(path.to_string(), |path, &next| {
-                path + "->" + &self.labels[next]
-            });
-        write!(f, "Path: {}, Cost: {}", path, self.cost)
+            .zip(self.path.iter().skip(1))
+            .map(|(&from, &to)| *weight_matrix.get((from, to))?)
+            .collect::<Option<Vec<_>>>()?;
+        W::checked_sum(weights)
+    }
+
+    /// Checks if the route ends at the node it starts at.
+    ///
+    /// Routes are circuits unless `SolverConfig::open` left them open. An empty route is not a
+    /// circuit.
+    pub fn is_circuit(&self) -> bool {
+        !self.path.is_empty() && self.path.front() == self.path.back()
+    }
+
+    /// Counts how many times the route walks each edge.
+    ///
+    /// Edges the solver repeated to balance the graph are counted more than once. Parallel edges
+    /// between the same nodes share one count.
+    ///
+    /// # Returns
+    ///
+    /// The number of traversals of each walked `(from, to)` edge.
+    pub fn edge_traversal_counts(&self) -> HashMap<(usize, usize), usize> {
+        let mut counts = HashMap::new();
+        for (&from, &to) in self.path.iter().zip(self.path.iter().skip(1)) {
+            *counts.entry((from, to)).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Checks that the route is a valid directed postman route of the graph.
+    ///
+    /// Every step must follow an existing edge with its own weight, the route must end where it
+    /// starts, and every edge of the graph must be walked at least once. Routes returned by
+    /// `solve_undirected` walk each street in one direction only, so they don't pass this check.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph given to the solver, before balancing.
+    pub fn verify(&self, graph: &Graph<W>) -> bool {
+        let edge_set = graph.weighted_edge_set();
+        let mut unwalked = edge_set.clone();
+        if self.path.front() != self.path.back() || self.weights.len() + 1 != self.path.len() {
+            return false;
+        }
+        for ((&from, &to), &weight) in self
+            .path
+            .iter()
+            .zip(self.path.iter().skip(1))
+            .zip(&self.weights)
+        {
+            let Some(edges) = edge_set.get(from) else {
+                return false;
+            };
+            if !edges.contains(&(to, weight)) {
+                return false;
+            }
+            if let Some(index) = unwalked[from].iter().position(|&edge| edge == (to, weight)) {
+                unwalked[from].swap_remove(index);
+            }
+        }
+        unwalked.iter().all(Vec::is_empty)
+    }
+
+    /// Renders the route over the graph in the Graphviz DOT format.
+    ///
+    /// Each edge walked by the route is drawn red and labeled with its weight and the order in
+    /// which it was walked. Walks that reuse an edge, added while balancing the graph, are drawn
+    /// as extra dashed edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph given to the solver, before balancing.
+    pub fn to_dot(&self, graph: &Graph<W>) -> String {
+        let mut unwalked = graph.weighted_edge_set();
+        let mut walks = vec![Vec::new(); unwalked.len()];
+        let mut extra_walks = Vec::new();
+        for (step, ((&from, &to), &weight)) in self
+            .path
+            .iter()
+            .zip(self.path.iter().skip(1))
+            .zip(&self.weights)
+            .enumerate()
+        {
+            let order = step + 1;
+            match unwalked[from].iter().position(|&edge| edge == (to, weight)) {
+                Some(index) => walks[from].push((unwalked[from].swap_remove(index), order)),
+                None => extra_walks.push((from, to, weight, order)),
+            }
+        }
+
+        let mut dot = graph.dot_nodes();
+        for (from, edges) in unwalked.into_iter().enumerate() {
+            for (to, weight) in edges {
+                dot += &format!("    {} -> {} [label=\"{}\"];\n", from, to, weight);
+            }
+        }
+        for (from, edges) in walks.into_iter().enumerate() {
+            for ((to, weight), order) in edges {
+                dot += &format!(
+                    "    {} -> {} [label=\"{} (#{})\", color=red];\n",
+                    from, to, weight, order
+                );
+            }
+        }
+        for (from, to, weight, order) in extra_walks {
+            dot += &format!(
+                "    {} -> {} [label=\"{} (#{})\", color=red, style=dashed];\n",
+                from, to, weight, order
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the route as a GeoJSON `FeatureCollection`, e.g. to draw it on a map.
+    ///
+    /// The collection holds one `LineString` feature through the coordinates of each node along
+    /// the route, with the cost of the route as a property.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph given to the solver, with coordinates from
+    ///   `GraphBuilder::add_node_coords`.
+    ///
+    /// # Returns
+    ///
+    /// The GeoJSON document, or `GraphError::MissingCoordinates` for the first node of the route
+    /// without coordinates.
+    pub fn to_geojson(&self, graph: &Graph<W>) -> Result<String, GraphError> {
+        let coordinates = self
+            .path
+            .iter()
+            .map(|&node| {
+                let (lon, lat) =
+                    graph
+                        .coordinates(node)
+                        .ok_or_else(|| GraphError::MissingCoordinates {
+                            label: self.labels[node].clone(),
+                        })?;
+                Ok(format!("[{},{}]", lon, lat))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(format!(
+            "{{\"type\":\"FeatureCollection\",\"features\":[{{\"type\":\"Feature\",\
+             \"geometry\":{{\"type\":\"LineString\",\"coordinates\":[{}]}},\
+             \"properties\":{{\"cost\":{}}}}}]}}",
+            coordinates.join(","),
+            self.cost
+        ))
+    }
+
+    /// Returns the node indices of the path as a contiguous slice, without allocating.
+    ///
+    /// This may rearrange the internal buffer of `path`, but never changes its logical order.
+    pub fn make_contiguous(&mut self) -> &[usize] {
+        self.path.make_contiguous()
+    }
+}
+
+/// Formats the path as `Path: A->B->C, Cost: 3`.
+///
+/// The precision flag applies to the cost, so `{:.2}` prints it with two decimals.
+impl<W: Weight> fmt::Display for Path<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.format("->");
+        match f.precision() {
+            Some(precision) => write!(f, "Path: {}, Cost: {:.*}", path, precision, self.cost),
+            None => write!(f, "Path: {}, Cost: {}", path, self.cost),
+        }
+    }
+}
+
+/// Shows the labels along the path and its costs, leaving out the labels of the whole graph.
+impl<W: Weight> fmt::Debug for Path<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Path")
+            .field("nodes", &self.nodes())
+            .field("cost", &self.cost)
+            .field("balancing_cost", &self.balancing_cost)
+            .finish()
+    }
+}
+
+/// Shows the graph and the options of the solver, leaving out the computed shortest paths.
+impl<W: Weight> fmt::Debug for CppSolver<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CppSolver")
+            .field("graph", &self.graph)
+            .field("config", &self.config)
+            .finish()
     }
 }
 
@@ -157,7 +1283,14 @@ fn test_solver_unsolvable_graph() {
     builder.add_edge(0, 1, 10.0).add_edge(1, 2, -20.0);
     let graph = builder.build();
     let mut solver = CppSolver::new(graph);
-    assert!(solver.solve().is_none());
+    assert_eq!(
+        solver.solve().err(),
+        Some(SolveError::NotStronglyConnected(vec![
+            vec![0],
+            vec![1],
+            vec![2]
+        ]))
+    );
 }
 
 /// Test that the solver correctly solves a simple, balanced graph.
@@ -169,21 +1302,15 @@ fn test_solver_simple_graph() {
     let graph = builder.build();
     let mut solver = CppSolver::new(graph);
     let solution = solver.solve();
-    assert!(solution.is_some());
-    assert_eq!(solution.unwrap().cost, 2.0);
+    assert!(solution.is_ok());
+    assert!(solution.unwrap().cost_approx_eq(2.0, DEFAULT_COST_EPSILON));
 }
 
 /// Test that a Path calculates its cost correctly.
 #[test]
 fn test_path_cost() {
-    use ndarray::array;
-    let weight_matrix = array![
-        [0.0, 1.0, f64::INFINITY],
-        [f64::INFINITY, 0.0, 2.0],
-        [f64::INFINITY, f64::INFINITY, 0.0]
-    ];
     let labels = vec!["A".to_string(), "B".to_string(), "C".to_string()];
-    let path = Path::new(vec![0, 1, 2].into_iter().collect(), &weight_matrix, &labels);
+    let path = Path::new(vec![0, 1, 2].into_iter().collect(), vec![1.0, 2.0], &labels).unwrap();
     assert_eq!(
         path.cost, 3.0,
         "The cost of the path should be the sum of the edge weights"
@@ -193,17 +1320,850 @@ fn test_path_cost() {
 /// Test that a Path formats its display correctly.
 #[test]
 fn test_path_display() {
-    use ndarray::array;
-    let weight_matrix = array![
-        [0.0, 1.0, f64::INFINITY],
-        [f64::INFINITY, 0.0, 2.0],
-        [f64::INFINITY, f64::INFINITY, 0.0]
-    ];
     let labels = vec!["A".to_string(), "B".to_string(), "C".to_string()];
-    let path = Path::new(vec![0, 1, 2].into_iter().collect(), &weight_matrix, &labels);
+    let path = Path::new(vec![0, 1, 2].into_iter().collect(), vec![1.0, 2.0], &labels).unwrap();
     assert_eq!(
         path.to_string(),
         "Path: A->B->C, Cost: 3",
         "The path display should match the expected format"
     );
 }
+
+/// Test that a Path joins its labels with a custom separator.
+#[test]
+fn test_path_format() {
+    let labels = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+    let path = Path::new(vec![0, 1, 2].into_iter().collect(), vec![1.0, 2.0], &labels).unwrap();
+    assert_eq!(path.format(" "), "A B C");
+    assert_eq!(path.format(","), "A,B,C");
+}
+
+/// Test that the running costs start at zero and end at the total cost.
+#[test]
+fn test_path_cumulative_costs() {
+    let labels = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+    let path = Path::new(
+        vec![0, 1, 2, 0].into_iter().collect(),
+        vec![1.0, 2.0, 4.0],
+        &labels,
+    )
+    .unwrap();
+    let cumulative_costs = path.cumulative_costs();
+    assert_eq!(cumulative_costs, [0.0, 1.0, 3.0, 7.0]);
+    assert_eq!(cumulative_costs.len(), path.path.len());
+    assert!(path.cost_approx_eq(*cumulative_costs.last().unwrap(), DEFAULT_COST_EPSILON));
+    assert!(Path::<f64>::new(VecDeque::new(), Vec::new(), &labels)
+        .unwrap()
+        .cumulative_costs()
+        .is_empty());
+}
+
+/// Test that costs are compared within the given epsilon.
+#[test]
+fn test_path_cost_approx_eq() {
+    let labels = vec!["A".to_string(), "B".to_string()];
+    let path = Path::new(vec![0, 1, 0].into_iter().collect(), vec![0.1, 0.2], &labels).unwrap();
+    assert_ne!(path.cost, 0.3);
+    assert!(path.cost_approx_eq(0.3, DEFAULT_COST_EPSILON));
+    assert!(!path.cost_approx_eq(0.31, DEFAULT_COST_EPSILON));
+}
+
+/// Test that a Path lists its node labels and labeled edges in order.
+#[test]
+fn test_path_nodes_and_edges() {
+    let labels = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+    let path = Path::new(vec![0, 1, 2].into_iter().collect(), vec![1.0, 2.0], &labels).unwrap();
+    assert_eq!(path.nodes(), vec!["A", "B", "C"]);
+    assert_eq!(
+        path.edges(),
+        vec![
+            ("A".to_string(), "B".to_string(), 1.0),
+            ("B".to_string(), "C".to_string(), 2.0)
+        ]
+    );
+    let total: f64 = path.edges().iter().map(|(_, _, weight)| weight).sum();
+    assert!(path.cost_approx_eq(total, DEFAULT_COST_EPSILON));
+}
+
+/// Test that the same route is priced under other weights, and not over missing edges.
+#[test]
+fn test_path_recost() {
+    use ndarray::arr2;
+    let labels = vec!["A".to_string(), "B".to_string()];
+    let path = Path::new(vec![0, 1, 0].into_iter().collect(), vec![1.0, 2.0], &labels).unwrap();
+    let off_peak = arr2(&[[None, Some(1.0)], [Some(2.0), None]]);
+    let rush_hour = arr2(&[[None, Some(4.0)], [Some(5.5), None]]);
+    assert!(path
+        .recost(off_peak.view())
+        .is_some_and(|cost| path.cost_approx_eq(cost, DEFAULT_COST_EPSILON)));
+    assert!(path
+        .recost(rush_hour.view())
+        .is_some_and(|cost| (cost - 9.5).abs() <= DEFAULT_COST_EPSILON));
+    let one_way = arr2(&[[None, Some(1.0)], [None, None]]);
+    assert_eq!(path.recost(one_way.view()), None);
+    assert_eq!(path.recost(arr2(&[[Some(1.0)]]).view()), None);
+}
+
+/// Test that the visit sequence numbers every visit, including repeated ones.
+#[test]
+fn test_path_visit_sequence() {
+    use crate::GraphBuilder;
+    // A figure eight of two loops meeting at C
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_labeled_edge("A", "B", 1.0)
+        .add_labeled_edge("B", "C", 1.0)
+        .add_labeled_edge("C", "A", 1.0)
+        .add_labeled_edge("C", "D", 1.0)
+        .add_labeled_edge("D", "E", 1.0)
+        .add_labeled_edge("E", "C", 1.0);
+    let config = SolverConfig {
+        canonical_output: true,
+        ..SolverConfig::default()
+    };
+    let path = CppSolver::with_config(builder.build(), config)
+        .solve()
+        .unwrap();
+    let expected = ["A", "B", "C", "D", "E", "C", "A"];
+    assert_eq!(
+        path.visit_sequence(),
+        expected
+            .iter()
+            .map(|label| label.to_string())
+            .enumerate()
+            .collect::<Vec<_>>()
+    );
+}
+
+/// Test that streaming the edges yields the same steps as collecting them.
+#[test]
+fn test_path_iter_edges() {
+    let labels = vec!["A".to_string(), "B".to_string()];
+    let path = Path::new(vec![0, 1, 0].into_iter().collect(), vec![1.5, 2.5], &labels).unwrap();
+    let mut edges = path.iter_edges();
+    assert_eq!(edges.next(), Some(("A", "B", 1.5)));
+    assert_eq!(edges.next(), Some(("B", "A", 2.5)));
+    assert_eq!(edges.next(), None);
+    let total: f64 = path.iter_edges().map(|(_, _, weight)| weight).sum();
+    assert!(path.cost_approx_eq(total, DEFAULT_COST_EPSILON));
+}
+
+/// Test that a Path exposes its nodes as a slice in the original order.
+#[test]
+fn test_path_make_contiguous() {
+    let labels = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+    let mut nodes = VecDeque::from(vec![1, 2]);
+    nodes.push_front(0);
+    let mut path = Path::new(nodes, vec![1.0, 2.0], &labels).unwrap();
+    assert_eq!(path.make_contiguous(), &[0, 1, 2]);
+}
+
+/// Test that a Path honours the formatter's precision for its cost.
+#[test]
+fn test_path_display_precision() {
+    let labels = vec!["A".to_string(), "B".to_string()];
+    let path = Path::new(
+        vec![0, 1, 0].into_iter().collect(),
+        vec![1.0 / 3.0, 2.0],
+        &labels,
+    )
+    .unwrap();
+    assert_eq!(format!("{:.2}", path), "Path: A->B->A, Cost: 2.33");
+    assert_eq!(format!("{:.0}", path), "Path: A->B->A, Cost: 2");
+}
+
+/// Test that the cheapest edge restoring strong connectivity is chosen.
+#[test]
+fn test_cheapest_connecting_edge() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(1, 0, 1.0)
+        .add_edge(1, 2, 1.0)
+        .add_edge(2, 3, 1.0);
+    let graph = builder.build();
+    let solver = CppSolver::new(graph);
+    let candidates = [(3, 0, 5.0), (3, 2, 1.0), (3, 1, 2.0), (0, 3, 0.5)];
+    assert_eq!(
+        solver.cheapest_connecting_edge(&candidates),
+        Some((3, 1, 2.0))
+    );
+    assert_eq!(solver.cheapest_connecting_edge(&candidates[1..2]), None);
+}
+
+/// Test that a negative cycle is reported as such.
+#[test]
+fn test_solver_negative_cycle() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder.add_edge(0, 1, 1.0).add_edge(1, 0, -2.0);
+    let graph = builder.build();
+    let mut solver = CppSolver::new(graph);
+    assert_eq!(solver.solve().err(), Some(SolveError::NegativeCycle));
+}
+
+/// Test that the canonical option yields the lexicographically smallest circuit.
+#[test]
+fn test_solver_canonical_output() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_undirected_edge(1, 2, 1.0)
+        .add_undirected_edge(0, 2, 1.0)
+        .add_undirected_edge(0, 1, 1.0);
+    let graph = builder.build();
+    let config = SolverConfig {
+        canonical_output: true,
+        ..SolverConfig::default()
+    };
+    let mut solver = CppSolver::with_config(graph, config);
+    let path = solver.solve().unwrap();
+    assert_eq!(path.path, [0, 1, 0, 2, 1, 2, 0]);
+    let path = solver.solve_undirected().unwrap();
+    assert_eq!(path.path, [0, 1, 2, 0]);
+}
+
+/// Test that a route repeating an edge more often than allowed is rejected.
+#[test]
+fn test_solver_max_traversals() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(1, 2, 1.0)
+        .add_edge(2, 0, 1.0)
+        .add_edge(0, 2, 1.0);
+    let graph = builder.build();
+    let config = SolverConfig {
+        max_traversals: Some(1),
+        ..SolverConfig::default()
+    };
+    let error = CppSolver::with_config(graph.clone(), config)
+        .solve()
+        .err()
+        .unwrap();
+    assert_eq!(
+        error,
+        SolveError::ConstraintViolation {
+            from: 2,
+            to: 0,
+            traversals: 2
+        }
+    );
+    let config = SolverConfig {
+        max_traversals: Some(2),
+        ..SolverConfig::default()
+    };
+    let path = CppSolver::with_config(graph, config).solve().unwrap();
+    assert!(path.cost_approx_eq(5.0, DEFAULT_COST_EPSILON));
+}
+
+/// Test that a route whose cost doesn't fit in the weight type is reported instead of
+/// panicking or costing infinity.
+#[test]
+fn test_solver_route_cost_overflow() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::<i64>::default();
+    builder
+        .add_edge(0, 1, i64::MAX / 2 + 1)
+        .add_edge(1, 0, i64::MAX / 2 + 1);
+    let graph = builder.build();
+    assert_eq!(
+        CppSolver::new(graph.clone()).solve().err(),
+        Some(SolveError::DistanceOverflow)
+    );
+    // Recosting the route on the graph's own weights overflows the same way
+    let path = Path::new(
+        VecDeque::from(vec![0, 1, 0]),
+        vec![1, 1],
+        graph.node_labels(),
+    )
+    .unwrap();
+    assert_eq!(path.recost(graph.weight_matrix().view()), None);
+
+    let mut builder = GraphBuilder::new();
+    builder.add_edge(0, 1, 1e308).add_edge(1, 0, 1e308);
+    assert_eq!(
+        CppSolver::new(builder.build()).solve().err(),
+        Some(SolveError::DistanceOverflow)
+    );
+}
+
+/// Test that distances overflowing to infinity are reported, and don't break connectivity.
+#[test]
+fn test_solver_overflowing_distances() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1e308)
+        .add_edge(1, 2, 1e308)
+        .add_edge(2, 3, 1e308)
+        .add_edge(3, 0, 1e308)
+        .add_edge(0, 2, 1e308);
+    let graph = builder.build();
+    let mut solver = CppSolver::new(graph);
+    assert_eq!(solver.solve().err(), Some(SolveError::DistanceOverflow));
+
+    // Two cycles joined in one direction only
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1e308)
+        .add_edge(1, 0, 1e308)
+        .add_edge(2, 3, 1e308)
+        .add_edge(3, 2, 1e308)
+        .add_edge(1, 2, 1e308);
+    let mut solver = CppSolver::new(builder.build());
+    assert_eq!(
+        solver.solve().err(),
+        Some(SolveError::NotStronglyConnected(vec![
+            vec![0, 1],
+            vec![2, 3]
+        ]))
+    );
+}
+
+/// Test that a negative edge without a negative cycle shortens the paths used for balancing.
+#[test]
+fn test_solver_negative_edge_balancing() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(1, 2, 1.0)
+        .add_edge(2, 0, 5.0)
+        .add_edge(0, 2, 3.0)
+        .add_edge(0, 2, 3.0)
+        .add_edge(2, 3, 4.0)
+        .add_edge(3, 0, -2.0);
+    let graph = builder.build();
+    let mut solver = CppSolver::new(graph.clone());
+    let path = solver.solve().unwrap();
+    // Going back from 2 to 0 through 3 costs 2, less than the direct edge of weight 5
+    assert!((path.balancing_cost - 2.0).abs() <= DEFAULT_COST_EPSILON);
+    assert!(path.cost_approx_eq(17.0, DEFAULT_COST_EPSILON));
+    assert_eq!(solver.shortest_route("2", "0"), ["2", "3", "0"]);
+    assert!(path.verify(&graph));
+}
+
+/// Test that an asymmetric graph can't be solved as undirected.
+#[test]
+fn test_solver_not_undirected() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder.add_edge(0, 1, 1.0).add_edge(1, 0, 2.0);
+    let graph = builder.build();
+    let mut solver = CppSolver::new(graph);
+    assert_eq!(
+        solver.solve_undirected().err(),
+        Some(SolveError::NotUndirected)
+    );
+}
+
+/// Test that solving from different start nodes rotates the same circuit.
+#[test]
+fn test_solver_solve_from() {
+    use crate::GraphBuilder;
+    let build = || {
+        let mut builder = GraphBuilder::new();
+        builder
+            .add_labeled_edge("a", "b", 1.0)
+            .add_labeled_edge("b", "c", 2.0)
+            .add_labeled_edge("c", "a", 3.0);
+        builder.build()
+    };
+    let from_a = CppSolver::new(build()).solve_from(0).unwrap();
+    let from_c = CppSolver::new(build()).solve_from(2).unwrap();
+    assert_eq!(from_a.to_string(), "Path: a->b->c->a, Cost: 6");
+    assert_eq!(from_c.to_string(), "Path: c->a->b->c, Cost: 6");
+    assert_eq!(
+        CppSolver::new(build()).solve_from(3).err(),
+        Some(SolveError::InvalidStartNode(3))
+    );
+}
+
+/// Test that parallel edges with different weights are each walked at their own cost.
+#[test]
+fn test_solver_parallel_edges() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 5.0)
+        .add_edge(0, 1, 3.0)
+        .add_edge(1, 0, 1.0);
+    let graph = builder.build();
+    let mut solver = CppSolver::new(graph);
+    let path = solver.solve().unwrap();
+    // Both parallel edges are walked, so the return edge is walked twice.
+    assert_eq!(path.path.len(), 5);
+    assert!(path.cost_approx_eq(10.0, DEFAULT_COST_EPSILON));
+}
+
+/// Test that the GeoJSON output traces the coordinates of the route in order.
+#[test]
+fn test_path_to_geojson() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_node_coords("A", 13.4, 52.5)
+        .add_node_coords("B", 2.35, 48.86)
+        .add_node_coords("C", -0.13, 51.51)
+        .add_labeled_edge("A", "B", 1.0)
+        .add_labeled_edge("B", "C", 1.0)
+        .add_labeled_edge("C", "A", 1.0);
+    let graph = builder.build();
+    let path = CppSolver::new(graph.clone()).solve().unwrap();
+    let geojson = path.to_geojson(&graph).unwrap();
+    assert!(serde_json::from_str::<serde_json::Value>(&geojson).is_ok());
+    assert_eq!(
+        geojson,
+        "{\"type\":\"FeatureCollection\",\"features\":[{\"type\":\"Feature\",\
+         \"geometry\":{\"type\":\"LineString\",\"coordinates\":\
+         [[13.4,52.5],[2.35,48.86],[-0.13,51.51],[13.4,52.5]]},\"properties\":{\"cost\":3}}]}"
+    );
+
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_node_coords("A", 13.4, 52.5)
+        .add_labeled_edge("A", "B", 1.0)
+        .add_labeled_edge("B", "A", 1.0);
+    let graph = builder.build();
+    let path = CppSolver::new(graph.clone()).solve().unwrap();
+    assert_eq!(
+        path.to_geojson(&graph),
+        Err(GraphError::MissingCoordinates {
+            label: "B".to_string()
+        })
+    );
+}
+
+/// Test that the DOT output of a route marks the edges duplicated by balancing as dashed.
+#[test]
+fn test_path_to_dot() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_labeled_edge("A", "B", 1.0)
+        .add_labeled_edge("A", "C", 2.0)
+        .add_labeled_edge("B", "A", 3.0)
+        .add_labeled_edge("C", "B", 4.0);
+    let graph = builder.build();
+    let path = CppSolver::new(graph.clone()).solve().unwrap();
+    let dot = path.to_dot(&graph);
+    assert!(dot.contains("    1 [label=\"B\"];\n"));
+    assert_eq!(dot.matches("color=red];").count(), 4);
+    assert_eq!(dot.matches("style=dashed").count(), 1);
+    assert!(dot.contains("    1 -> 0 [label=\"3 (#"));
+}
+
+/// Test that huge weights, whose shortest distances overflow to infinity, still count as edges.
+#[test]
+fn test_solver_huge_weights() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1e308)
+        .add_edge(1, 2, -1e308)
+        .add_edge(2, 0, 1e308);
+    let graph = builder.build();
+    assert_eq!(graph.out_degrees().to_vec(), vec![1, 1, 1]);
+    let mut path = CppSolver::new(graph).solve().unwrap();
+    assert_eq!(path.make_contiguous(), &[0, 1, 2, 0]);
+    assert!(path.cost_approx_eq(1e308, DEFAULT_COST_EPSILON));
+}
+
+/// Test that a solved route passes verification, and corrupted routes don't.
+#[test]
+fn test_path_verify() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(0, 2, 2.0)
+        .add_edge(1, 0, 3.0)
+        .add_edge(2, 1, 4.0);
+    let graph = builder.build();
+    let path = CppSolver::new(graph.clone()).solve().unwrap();
+    assert!(path.verify(&graph));
+
+    // A step along a missing edge
+    let mut corrupted = CppSolver::new(graph.clone()).solve().unwrap();
+    corrupted.path[1] = 2;
+    corrupted.path[2] = 0;
+    assert!(!corrupted.verify(&graph));
+
+    // A route skipping a required edge
+    let labels = vec!["0".to_string(), "1".to_string(), "2".to_string()];
+    let shortcut = Path::new(VecDeque::from(vec![0, 1, 0]), vec![1.0, 3.0], &labels).unwrap();
+    assert!(!shortcut.verify(&graph));
+}
+
+/// Test that solving twice with the same solver balances once and yields the same route.
+#[test]
+fn test_solver_repeated_solve() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(0, 2, 2.0)
+        .add_edge(1, 0, 3.0)
+        .add_edge(2, 1, 4.0);
+    let mut solver = CppSolver::new(builder.build());
+    let first = solver.solve().unwrap();
+    let second = solver.solve().unwrap();
+    assert_eq!(second.path, first.path);
+    assert!(second.cost_approx_eq(first.cost, DEFAULT_COST_EPSILON));
+    assert!(first.balancing_cost > 0.0);
+    assert!((second.balancing_cost - first.balancing_cost).abs() <= DEFAULT_COST_EPSILON);
+}
+
+/// Test that an already balanced graph is solved without computing all-pairs shortest paths.
+#[test]
+fn test_solver_balanced_graph_skips_floyd_warshall() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    for i in 0..5 {
+        builder.add_edge(i, (i + 1) % 5, 1.0);
+    }
+    let mut solver = CppSolver::new(builder.build());
+    let path = solver.solve().unwrap();
+    assert!(solver.floyd_warshall.get().is_none());
+    assert_eq!(path.path, [0, 1, 2, 3, 4, 0]);
+    assert!(path.cost_approx_eq(5.0, DEFAULT_COST_EPSILON));
+    assert!((path.balancing_cost - 0.0).abs() <= DEFAULT_COST_EPSILON);
+
+    // A negative cycle is still detected without Floyd-Warshall
+    let mut builder = GraphBuilder::new();
+    builder.add_edge(0, 1, 1.0).add_edge(1, 0, -2.0);
+    let mut solver = CppSolver::new(builder.build());
+    assert_eq!(solver.solve().err(), Some(SolveError::NegativeCycle));
+    assert!(solver.floyd_warshall.get().is_none());
+}
+
+/// Test that solves from several start nodes and configs share one Floyd-Warshall run.
+#[test]
+fn test_solver_precompute() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(0, 2, 2.0)
+        .add_edge(1, 0, 3.0)
+        .add_edge(2, 1, 4.0);
+    let mut solver = CppSolver::new(builder.build());
+    let oracle = solver.precompute();
+    assert_eq!(oracle.node_count(), 3);
+    assert_eq!(oracle.shortest_distance(2, 0), Some(7.0));
+    assert_eq!(oracle.shortest_path_between(2, 0), Some(vec![2, 1, 0]));
+
+    // Every Floyd-Warshall run makes a new runner, so still sharing the precomputed one shows
+    // that the solves didn't run it again
+    let costs: Vec<f64> = (0..3)
+        .map(|start| solver.solve_from(start).unwrap().cost)
+        .collect();
+    assert!(costs
+        .iter()
+        .all(|cost| (cost - 13.0).abs() <= DEFAULT_COST_EPSILON));
+    assert!(Arc::ptr_eq(
+        &oracle.runner,
+        solver.floyd_warshall.get().unwrap()
+    ));
+    solver.set_config(SolverConfig {
+        matching: MatchingStrategy::Greedy,
+        canonical_output: true,
+        ..SolverConfig::default()
+    });
+    assert!(solver
+        .solve()
+        .unwrap()
+        .cost_approx_eq(13.0, DEFAULT_COST_EPSILON));
+    let clone = solver.clone();
+    assert_eq!(clone.shortest_distance("2", "0"), Some(7.0));
+    assert!(Arc::ptr_eq(
+        &oracle.runner,
+        clone.floyd_warshall.get().unwrap()
+    ));
+
+    // A new edge updates the solver's shortest paths, but not the oracle's
+    solver.add_edge(2, 0, 1.0);
+    assert_eq!(solver.shortest_distance("2", "0"), Some(1.0));
+    assert_eq!(oracle.shortest_distance(2, 0), Some(7.0));
+}
+
+/// Test that progress messages go through the `log` crate instead of stdout.
+#[test]
+fn test_solver_logs_progress() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder.add_edge(0, 1, 1.0).add_edge(1, 0, 1.0);
+    let graph = builder.build();
+    let messages = log_capture::capture(|| {
+        CppSolver::new(graph).solve().unwrap();
+    });
+    assert!(messages.iter().any(|message| message.contains("solvable")));
+    assert!(messages
+        .iter()
+        .any(|message| message.contains("already balanced")));
+}
+
+/// Captures the messages logged by the current thread, so tests logging concurrently don't see
+/// each other's messages.
+///
+/// The capturing logger is the only logger of the test binary. It is installed by the first
+/// capture and kept for the rest of the run.
+#[cfg(test)]
+mod log_capture {
+    use std::{cell::RefCell, sync::OnceLock};
+
+    struct ThreadLogger;
+
+    thread_local! {
+        static MESSAGES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    impl log::Log for ThreadLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            MESSAGES.with(|messages| messages.borrow_mut().push(record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: ThreadLogger = ThreadLogger;
+    static INSTALLED: OnceLock<()> = OnceLock::new();
+
+    /// Runs a function and returns the messages it logged on this thread.
+    ///
+    /// # Panics
+    ///
+    /// If a logger other than the capturing one was installed, which no test may do.
+    pub fn capture(f: impl FnOnce()) -> Vec<String> {
+        INSTALLED.get_or_init(|| {
+            log::set_logger(&LOGGER).expect("only the capturing logger may be installed");
+            log::set_max_level(log::LevelFilter::Debug);
+        });
+        MESSAGES.with(|messages| messages.borrow_mut().clear());
+        f();
+        MESSAGES.with(RefCell::take)
+    }
+}
+
+/// Test that every way of solving an empty graph reports it as empty instead of panicking.
+#[test]
+fn test_solver_empty_graph() {
+    use crate::GraphBuilder;
+    for strategy in [
+        ShortestPathStrategy::FloydWarshall,
+        ShortestPathStrategy::Dijkstra,
+        ShortestPathStrategy::Johnson,
+    ] {
+        let mut solver = CppSolver::with_strategy(GraphBuilder::new().build(), strategy);
+        assert_eq!(solver.solve().err(), Some(SolveError::EmptyGraph));
+        assert_eq!(solver.solve_from(0).err(), Some(SolveError::EmptyGraph));
+        assert_eq!(
+            solver.solve_undirected().err(),
+            Some(SolveError::EmptyGraph)
+        );
+    }
+}
+
+/// Test that a graph made of a single self-loop is solved by walking it once.
+#[test]
+fn test_solver_single_self_loop() {
+    let graph = Graph::from_adjacency_list(vec![vec![(0, 2.0)]], None);
+    let path = CppSolver::new(graph).solve().unwrap();
+    assert_eq!(path.path, [0, 0]);
+    assert!(path.cost_approx_eq(2.0, DEFAULT_COST_EPSILON));
+}
+
+/// Test that the configured start node is used by `solve`.
+#[test]
+fn test_solver_with_config() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(1, 2, 1.0)
+        .add_edge(2, 0, 1.0);
+    let config = SolverConfig {
+        start: 2,
+        ..SolverConfig::default()
+    };
+    let path = CppSolver::with_config(builder.build(), config)
+        .solve()
+        .unwrap();
+    assert_eq!(path.path, [2, 0, 1, 2]);
+
+    let config = SolverConfig {
+        start: 3,
+        ..SolverConfig::default()
+    };
+    let mut builder = GraphBuilder::new();
+    builder.add_edge(0, 1, 1.0).add_edge(1, 0, 1.0);
+    let mut solver = CppSolver::with_config(builder.build(), config);
+    assert_eq!(solver.solve().err(), Some(SolveError::InvalidStartNode(3)));
+}
+
+/// Test that a solve past its timeout fails, while a generous timeout changes nothing.
+#[test]
+fn test_solver_timeout() {
+    use crate::GraphBuilder;
+    use std::time::Duration;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_undirected_edge(0, 1, 1.0)
+        .add_undirected_edge(1, 2, 1.0)
+        .add_undirected_edge(2, 0, 1.0)
+        .add_undirected_edge(2, 3, 1.0)
+        .add_edge(0, 2, 1.0);
+    let graph = builder.build();
+    for strategy in [
+        ShortestPathStrategy::FloydWarshall,
+        ShortestPathStrategy::Dijkstra,
+        ShortestPathStrategy::Johnson,
+    ] {
+        let config = SolverConfig {
+            strategy,
+            timeout: Some(Duration::ZERO),
+            ..SolverConfig::default()
+        };
+        let mut solver = CppSolver::with_config(graph.clone(), config);
+        assert_eq!(solver.solve().err(), Some(SolveError::TimedOut));
+
+        let config = SolverConfig {
+            timeout: Some(Duration::from_secs(60)),
+            ..config
+        };
+        let path = CppSolver::with_config(graph.clone(), config)
+            .solve()
+            .unwrap();
+        assert_eq!(
+            path.cost,
+            CppSolver::new(graph.clone()).solve().unwrap().cost
+        );
+    }
+
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_undirected_edge(0, 1, 1.0)
+        .add_undirected_edge(1, 2, 1.0)
+        .add_undirected_edge(2, 0, 1.0)
+        .add_undirected_edge(2, 3, 1.0);
+    let config = SolverConfig {
+        timeout: Some(Duration::ZERO),
+        ..SolverConfig::default()
+    };
+    let mut solver = CppSolver::with_config(builder.build(), config);
+    assert_eq!(solver.solve_undirected().err(), Some(SolveError::TimedOut));
+}
+
+/// Test that the cost of a long route doesn't drift from rounding errors.
+#[test]
+fn test_path_cost_compensated() {
+    let labels = vec!["A".to_string(), "B".to_string()];
+    let path = (0..=10_000).map(|i| i % 2).collect();
+    let weights = vec![0.1; 10_000];
+    let naive: f64 = weights.iter().sum();
+    assert!((naive - 1000.0).abs() > 1e-10);
+    let path = Path::new(path, weights, &labels).unwrap();
+    assert!((path.cost - 1000.0).abs() < 1e-12);
+}
+
+/// Test that solving a clone leaves the original graph and solver untouched.
+#[test]
+fn test_clone_and_debug() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_labeled_edge("a", "b", 1.0)
+        .add_labeled_edge("a", "c", 2.0)
+        .add_labeled_edge("b", "a", 3.0)
+        .add_labeled_edge("c", "b", 4.0);
+    let graph = builder.build();
+    let solver = CppSolver::new(graph.clone());
+    let path = solver.clone().solve().unwrap();
+    assert_eq!(graph.edge_count(), 4);
+    assert!(path.verify(&graph));
+    assert_eq!(
+        format!("{:?}", graph),
+        r#"Graph { node_count: 3, edge_count: 4, node_labels: ["a", "b", "c"] }"#
+    );
+    assert_eq!(
+        format!("{:?}", solver),
+        format!(
+            "CppSolver {{ graph: {:?}, config: {:?} }}",
+            graph,
+            SolverConfig::default()
+        )
+    );
+    assert_eq!(
+        format!("{:?}", path.clone()),
+        r#"Path { nodes: ["a", "c", "b", "a", "b", "a"], cost: 13.0, balancing_cost: 3.0 }"#
+    );
+}
+
+/// Test that a semi-Eulerian graph gets an open route without balancing, if allowed.
+#[test]
+fn test_solver_open_route() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(1, 2, 2.0)
+        .add_edge(2, 0, 3.0)
+        .add_edge(2, 1, 4.0);
+    let graph = builder.build();
+    let config = SolverConfig {
+        open: true,
+        ..SolverConfig::default()
+    };
+    let path = CppSolver::with_config(graph.clone(), config)
+        .solve()
+        .unwrap();
+    assert_eq!(path.path, [2, 1, 2, 0, 1]);
+    assert!(path.cost_approx_eq(10.0, DEFAULT_COST_EPSILON));
+    assert!((path.balancing_cost - 0.0).abs() <= DEFAULT_COST_EPSILON);
+    assert!(!path.is_circuit());
+
+    // Without the option, the route comes back from node 1 to node 2
+    let path = CppSolver::new(graph).solve().unwrap();
+    assert!(path.cost_approx_eq(12.0, DEFAULT_COST_EPSILON));
+    assert!(path.is_circuit());
+}
+
+/// Test that adding an edge to a solver updates its shortest paths like a full recomputation.
+#[test]
+fn test_solver_add_edge() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 4.0)
+        .add_edge(1, 2, 4.0)
+        .add_edge(2, 3, 4.0)
+        .add_edge(3, 0, 4.0)
+        .add_edge(0, 2, 9.0);
+    let mut solver = CppSolver::new(builder.build());
+    solver.solve().unwrap();
+    solver.add_edge(3, 1, 1.0);
+    solver.add_edge(2, 0, -1.0);
+
+    let fresh = FloydWarshallRunner::new(solver.graph.weight_matrix().view());
+    let updated = solver.floyd_warshall();
+    assert_eq!(updated.shortest_distances(), fresh.shortest_distances());
+    for from in 0..4 {
+        for to in 0..4 {
+            assert_eq!(
+                updated.shortest_path_between(from, to).len(),
+                fresh.shortest_path_between(from, to).len()
+            );
+        }
+    }
+
+    let path = solver.solve().unwrap();
+    let fresh_cost = CppSolver::new(solver.graph.clone()).solve().unwrap().cost;
+    assert!(path.cost_approx_eq(fresh_cost, DEFAULT_COST_EPSILON));
+}
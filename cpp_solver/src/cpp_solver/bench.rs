@@ -0,0 +1,34 @@
+//! Runs single stages of the solver on their own, so the benchmarks can time them.
+//!
+//! Only built with the `bench` feature. These functions are not part of the stable API.
+
+use std::hint::black_box;
+
+use super::{floyd_warshall::FloydWarshallRunner, hungarian};
+use crate::{Graph, ImbalancedNodeSet, Weight};
+
+/// Runs Floyd-Warshall on the graph, as the solver does before balancing.
+pub fn floyd_warshall<W: Weight>(graph: &Graph<W>) {
+    black_box(FloydWarshallRunner::new(graph.weight_matrix().view()));
+}
+
+/// Returns the nodes the solver has to balance.
+pub fn imbalanced_nodes<W: Weight>(graph: &Graph<W>) -> ImbalancedNodeSet {
+    graph.imbalanced_nodes()
+}
+
+/// Pairs the imbalanced nodes with the Hungarian algorithm.
+///
+/// # Returns
+///
+/// The `(from, to)` pairs of the matching.
+pub fn hungarian_match(
+    imbalanced_nodes: &ImbalancedNodeSet,
+    shortest_distance: impl Fn(usize, usize) -> f64 + Sync,
+) -> Vec<(usize, usize)> {
+    hungarian::best_match(imbalanced_nodes, shortest_distance, None)
+        .expect("a matching without a deadline always finishes")
+        .into_iter()
+        .map(|matching| (matching.from, matching.to))
+        .collect()
+}
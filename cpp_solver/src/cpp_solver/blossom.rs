@@ -0,0 +1,753 @@
+use std::time::Instant;
+
+use super::{hungarian::Matching, is_past};
+
+/// Marker for an absent vertex, edge endpoint, or blossom.
+const NONE: usize = usize::MAX;
+
+/// Finds the minimum-weight perfect matching between odd-degree nodes based on the shortest distance between them.
+///
+/// # Arguments
+///
+/// * `odd_nodes` - The nodes with an odd undirected degree. Their count must be even.
+/// * `shortest_distance` - Returns the shortest distance between two nodes. It must be finite,
+///   as an infinite one turns the weights of the matching into NaN.
+/// * `deadline` - The time by which the matching must be found, checked before each row of
+///   distances and before each stage of the Blossom algorithm.
+///
+/// # Returns
+///
+/// A vector of `Matching` structs pairing up every odd-degree node, or `None` if the deadline
+/// passed.
+pub(super) fn best_match(
+    odd_nodes: &[usize],
+    shortest_distance: impl Fn(usize, usize) -> f64,
+    deadline: Option<Instant>,
+) -> Option<Vec<Matching>> {
+    let distance = |i: usize, j: usize| shortest_distance(odd_nodes[i], odd_nodes[j]);
+    let mut edges = Vec::new();
+    for i in 0..odd_nodes.len() {
+        if is_past(deadline) {
+            return None;
+        }
+        for j in i + 1..odd_nodes.len() {
+            edges.push((i, j, distance(i, j)));
+        }
+    }
+
+    // A maximum-cardinality matching maximising `offset - distance` is a
+    // minimum-weight perfect matching on the complete graph.
+    let offset = edges.iter().map(|&(_, _, w)| w).fold(0., f64::max) + 1.;
+    for edge in edges.iter_mut() {
+        edge.2 = offset - edge.2;
+    }
+
+    let mate = BlossomMatcher::new(&edges).run(deadline)?;
+    Some(
+        mate.iter()
+            .enumerate()
+            .filter(|&(i, &j)| j != NONE && i < j)
+            .map(|(i, &j)| Matching {
+                from: odd_nodes[i],
+                to: odd_nodes[j],
+            })
+            .collect(),
+    )
+}
+
+/// Edmonds' blossom algorithm for maximum-weight matching in general graphs.
+///
+/// Follows the primal-dual formulation by Galil, "Efficient Algorithms for Finding
+/// Maximum Matching in Graphs", and runs in O(n³). Cardinality is always maximised
+/// first, so a complete graph with an even number of vertices yields a perfect matching.
+struct BlossomMatcher<'a> {
+    edges: &'a [(usize, usize, f64)],
+    n_vertices: usize,
+    /// `endpoint[p]` is the vertex at endpoint `p`; edge `k` has endpoints `2k` and `2k + 1`.
+    endpoint: Vec<usize>,
+    /// Endpoints of the edges incident to each vertex, pointing away from it.
+    neighbour_ends: Vec<Vec<usize>>,
+    /// Remote endpoint of the matched edge of each vertex.
+    mate: Vec<usize>,
+    /// 0 for free, 1 for S, 2 for T; 5 marks a breadcrumb during `scan_blossom`.
+    label: Vec<u8>,
+    label_end: Vec<usize>,
+    in_blossom: Vec<usize>,
+    blossom_parent: Vec<usize>,
+    blossom_children: Vec<Vec<usize>>,
+    blossom_base: Vec<usize>,
+    blossom_endpoints: Vec<Vec<usize>>,
+    best_edge: Vec<usize>,
+    blossom_best_edges: Vec<Option<Vec<usize>>>,
+    unused_blossoms: Vec<usize>,
+    dual: Vec<f64>,
+    allow_edge: Vec<bool>,
+    queue: Vec<usize>,
+}
+
+impl<'a> BlossomMatcher<'a> {
+    /// Initializes the matcher with an empty matching and feasible duals.
+    fn new(edges: &'a [(usize, usize, f64)]) -> Self {
+        let n_vertices = edges
+            .iter()
+            .map(|&(i, j, _)| i.max(j) + 1)
+            .max()
+            .unwrap_or(0);
+        let max_weight = edges.iter().map(|&(_, _, w)| w).fold(0., f64::max);
+
+        let endpoint = (0..2 * edges.len())
+            .map(|p| {
+                let (i, j, _) = edges[p / 2];
+                if p % 2 == 0 {
+                    i
+                } else {
+                    j
+                }
+            })
+            .collect();
+        let mut neighbour_ends = vec![Vec::new(); n_vertices];
+        for (k, &(i, j, _)) in edges.iter().enumerate() {
+            neighbour_ends[i].push(2 * k + 1);
+            neighbour_ends[j].push(2 * k);
+        }
+
+        Self {
+            edges,
+            n_vertices,
+            endpoint,
+            neighbour_ends,
+            mate: vec![NONE; n_vertices],
+            label: vec![0; 2 * n_vertices],
+            label_end: vec![NONE; 2 * n_vertices],
+            in_blossom: (0..n_vertices).collect(),
+            blossom_parent: vec![NONE; 2 * n_vertices],
+            blossom_children: vec![Vec::new(); 2 * n_vertices],
+            blossom_base: (0..n_vertices)
+                .chain(std::iter::repeat_n(NONE, n_vertices))
+                .collect(),
+            blossom_endpoints: vec![Vec::new(); 2 * n_vertices],
+            best_edge: vec![NONE; 2 * n_vertices],
+            blossom_best_edges: vec![None; 2 * n_vertices],
+            unused_blossoms: (n_vertices..2 * n_vertices).collect(),
+            dual: std::iter::repeat_n(max_weight, n_vertices)
+                .chain(std::iter::repeat_n(0., n_vertices))
+                .collect(),
+            allow_edge: vec![false; edges.len()],
+            queue: Vec::new(),
+        }
+    }
+
+    /// Computes the matching and returns the mate of each vertex, or `NONE` if it is unmatched.
+    ///
+    /// Each stage takes O(n²), so the deadline is checked before each one. Returns `None` if it
+    /// passed.
+    fn run(mut self, deadline: Option<Instant>) -> Option<Vec<usize>> {
+        let n = self.n_vertices;
+        for _ in 0..n {
+            if is_past(deadline) {
+                return None;
+            }
+            // Start a new stage: every free vertex becomes the root of an alternating tree.
+            self.label.fill(0);
+            self.best_edge.fill(NONE);
+            for b in n..2 * n {
+                self.blossom_best_edges[b] = None;
+            }
+            self.allow_edge.fill(false);
+            self.queue.clear();
+            for v in 0..n {
+                if self.mate[v] == NONE && self.label[self.in_blossom[v]] == 0 {
+                    self.assign_label(v, 1, NONE);
+                }
+            }
+
+            let mut augmented = false;
+            loop {
+                while !augmented {
+                    let Some(v) = self.queue.pop() else { break };
+                    augmented = self.scan_neighbours(v);
+                }
+                if augmented {
+                    break;
+                }
+                if !self.update_duals() {
+                    break;
+                }
+            }
+            if !augmented {
+                break;
+            }
+
+            // Expand S-blossoms whose dual dropped to zero before the next stage.
+            for b in n..2 * n {
+                if self.blossom_parent[b] == NONE
+                    && self.blossom_base[b] != NONE
+                    && self.label[b] == 1
+                    && self.dual[b] == 0.
+                {
+                    self.expand_blossom(b, true);
+                }
+            }
+        }
+
+        Some(
+            (0..n)
+                .map(|v| match self.mate[v] {
+                    NONE => NONE,
+                    p => self.endpoint[p],
+                })
+                .collect(),
+        )
+    }
+
+    /// Scans the edges of the S-vertex `v`, growing the tree, forming blossoms, or augmenting.
+    ///
+    /// Returns `true` if the matching was augmented.
+    fn scan_neighbours(&mut self, v: usize) -> bool {
+        for idx in 0..self.neighbour_ends[v].len() {
+            let p = self.neighbour_ends[v][idx];
+            let k = p / 2;
+            let w = self.endpoint[p];
+            if self.in_blossom[v] == self.in_blossom[w] {
+                continue;
+            }
+            let mut k_slack = 0.;
+            if !self.allow_edge[k] {
+                k_slack = self.slack(k);
+                if k_slack <= 0. {
+                    self.allow_edge[k] = true;
+                }
+            }
+            if self.allow_edge[k] {
+                if self.label[self.in_blossom[w]] == 0 {
+                    self.assign_label(w, 2, p ^ 1);
+                } else if self.label[self.in_blossom[w]] == 1 {
+                    match self.scan_blossom(v, w) {
+                        NONE => {
+                            self.augment_matching(k);
+                            return true;
+                        }
+                        base => self.add_blossom(base, k),
+                    }
+                } else if self.label[w] == 0 {
+                    self.label[w] = 2;
+                    self.label_end[w] = p ^ 1;
+                }
+            } else if self.label[self.in_blossom[w]] == 1 {
+                let b = self.in_blossom[v];
+                if self.best_edge[b] == NONE || k_slack < self.slack(self.best_edge[b]) {
+                    self.best_edge[b] = k;
+                }
+            } else if self.label[w] == 0
+                && (self.best_edge[w] == NONE || k_slack < self.slack(self.best_edge[w]))
+            {
+                self.best_edge[w] = k;
+            }
+        }
+        false
+    }
+
+    /// Adjusts the dual variables by the largest feasible step.
+    ///
+    /// Returns `false` if no further progress is possible in this stage.
+    fn update_duals(&mut self) -> bool {
+        let n = self.n_vertices;
+        let mut delta_type = 0;
+        let mut delta = 0.;
+        let mut delta_edge = NONE;
+        let mut delta_blossom = NONE;
+
+        for v in 0..n {
+            if self.label[self.in_blossom[v]] == 0 && self.best_edge[v] != NONE {
+                let d = self.slack(self.best_edge[v]);
+                if delta_type == 0 || d < delta {
+                    delta = d;
+                    delta_type = 2;
+                    delta_edge = self.best_edge[v];
+                }
+            }
+        }
+        for b in 0..2 * n {
+            if self.blossom_parent[b] == NONE && self.label[b] == 1 && self.best_edge[b] != NONE {
+                let d = self.slack(self.best_edge[b]) / 2.;
+                if delta_type == 0 || d < delta {
+                    delta = d;
+                    delta_type = 3;
+                    delta_edge = self.best_edge[b];
+                }
+            }
+        }
+        for b in n..2 * n {
+            if self.blossom_base[b] != NONE
+                && self.blossom_parent[b] == NONE
+                && self.label[b] == 2
+                && (delta_type == 0 || self.dual[b] < delta)
+            {
+                delta = self.dual[b];
+                delta_type = 4;
+                delta_blossom = b;
+            }
+        }
+        if delta_type == 0 {
+            // No further improvement possible; stop at maximum cardinality.
+            delta_type = 1;
+            delta = self.dual[..n]
+                .iter()
+                .cloned()
+                .fold(f64::INFINITY, f64::min)
+                .max(0.);
+        }
+
+        for v in 0..n {
+            match self.label[self.in_blossom[v]] {
+                1 => self.dual[v] -= delta,
+                2 => self.dual[v] += delta,
+                _ => (),
+            }
+        }
+        for b in n..2 * n {
+            if self.blossom_base[b] != NONE && self.blossom_parent[b] == NONE {
+                match self.label[b] {
+                    1 => self.dual[b] += delta,
+                    2 => self.dual[b] -= delta,
+                    _ => (),
+                }
+            }
+        }
+
+        match delta_type {
+            2 => {
+                self.allow_edge[delta_edge] = true;
+                let (mut i, j, _) = self.edges[delta_edge];
+                if self.label[self.in_blossom[i]] == 0 {
+                    i = j;
+                }
+                self.queue.push(i);
+            }
+            3 => {
+                self.allow_edge[delta_edge] = true;
+                let (i, _, _) = self.edges[delta_edge];
+                self.queue.push(i);
+            }
+            4 => self.expand_blossom(delta_blossom, false),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Returns twice the reduced cost of edge `k`.
+    fn slack(&self, k: usize) -> f64 {
+        let (i, j, w) = self.edges[k];
+        self.dual[i] + self.dual[j] - 2. * w
+    }
+
+    /// Collects the vertices contained in blossom `b`.
+    fn blossom_leaves(&self, b: usize) -> Vec<usize> {
+        if b < self.n_vertices {
+            return vec![b];
+        }
+        self.blossom_children[b]
+            .iter()
+            .flat_map(|&child| self.blossom_leaves(child))
+            .collect()
+    }
+
+    /// Labels vertex `w` and its blossom with `t`, reached through endpoint `p`.
+    fn assign_label(&mut self, w: usize, t: u8, p: usize) {
+        let b = self.in_blossom[w];
+        self.label[w] = t;
+        self.label[b] = t;
+        self.label_end[w] = p;
+        self.label_end[b] = p;
+        self.best_edge[w] = NONE;
+        self.best_edge[b] = NONE;
+        if t == 1 {
+            let leaves = self.blossom_leaves(b);
+            self.queue.extend(leaves);
+        } else if t == 2 {
+            let base = self.blossom_base[b];
+            let mate = self.mate[base];
+            self.assign_label(self.endpoint[mate], 1, mate ^ 1);
+        }
+    }
+
+    /// Traces back from `v` and `w` to find the base of a new blossom, or `NONE` for an augmenting path.
+    fn scan_blossom(&mut self, mut v: usize, mut w: usize) -> usize {
+        let mut path = Vec::new();
+        let mut base = NONE;
+        while v != NONE || w != NONE {
+            let mut b = self.in_blossom[v];
+            if self.label[b] & 4 != 0 {
+                base = self.blossom_base[b];
+                break;
+            }
+            path.push(b);
+            self.label[b] = 5;
+            if self.label_end[b] == NONE {
+                v = NONE;
+            } else {
+                v = self.endpoint[self.label_end[b]];
+                b = self.in_blossom[v];
+                v = self.endpoint[self.label_end[b]];
+            }
+            if w != NONE {
+                std::mem::swap(&mut v, &mut w);
+            }
+        }
+        for b in path {
+            self.label[b] = 1;
+        }
+        base
+    }
+
+    /// Creates a new blossom with the given base, closed by edge `k`.
+    fn add_blossom(&mut self, base: usize, k: usize) {
+        let (mut v, mut w, _) = self.edges[k];
+        let bb = self.in_blossom[base];
+        let mut bv = self.in_blossom[v];
+        let mut bw = self.in_blossom[w];
+        let b = self.unused_blossoms.pop().expect("blossom pool exhausted");
+        self.blossom_base[b] = base;
+        self.blossom_parent[b] = NONE;
+        self.blossom_parent[bb] = b;
+
+        let mut path = Vec::new();
+        let mut endpoints = Vec::new();
+        while bv != bb {
+            self.blossom_parent[bv] = b;
+            path.push(bv);
+            endpoints.push(self.label_end[bv]);
+            v = self.endpoint[self.label_end[bv]];
+            bv = self.in_blossom[v];
+        }
+        path.push(bb);
+        path.reverse();
+        endpoints.reverse();
+        endpoints.push(2 * k);
+        while bw != bb {
+            self.blossom_parent[bw] = b;
+            path.push(bw);
+            endpoints.push(self.label_end[bw] ^ 1);
+            w = self.endpoint[self.label_end[bw]];
+            bw = self.in_blossom[w];
+        }
+
+        self.label[b] = 1;
+        self.label_end[b] = self.label_end[bb];
+        self.dual[b] = 0.;
+        for leaf in self.blossom_leaves_of(&path) {
+            if self.label[self.in_blossom[leaf]] == 2 {
+                self.queue.push(leaf);
+            }
+            self.in_blossom[leaf] = b;
+        }
+
+        // Compute the least-slack edges from the new blossom to each neighbouring S-blossom.
+        let mut best_edge_to = vec![NONE; 2 * self.n_vertices];
+        for &child in &path {
+            let neighbour_lists = match self.blossom_best_edges[child].take() {
+                Some(list) => vec![list],
+                None => self
+                    .blossom_leaves(child)
+                    .into_iter()
+                    .map(|leaf| self.neighbour_ends[leaf].iter().map(|p| p / 2).collect())
+                    .collect(),
+            };
+            for list in neighbour_lists {
+                for k in list {
+                    let (mut i, mut j, _) = self.edges[k];
+                    if self.in_blossom[j] == b {
+                        std::mem::swap(&mut i, &mut j);
+                    }
+                    let bj = self.in_blossom[j];
+                    if bj != b
+                        && self.label[bj] == 1
+                        && (best_edge_to[bj] == NONE
+                            || self.slack(k) < self.slack(best_edge_to[bj]))
+                    {
+                        best_edge_to[bj] = k;
+                    }
+                }
+            }
+            self.best_edge[child] = NONE;
+        }
+        let best_edges: Vec<usize> = best_edge_to.into_iter().filter(|&k| k != NONE).collect();
+        self.best_edge[b] = NONE;
+        for &k in &best_edges {
+            if self.best_edge[b] == NONE || self.slack(k) < self.slack(self.best_edge[b]) {
+                self.best_edge[b] = k;
+            }
+        }
+        self.blossom_best_edges[b] = Some(best_edges);
+        self.blossom_children[b] = path;
+        self.blossom_endpoints[b] = endpoints;
+    }
+
+    /// Collects the vertices of every blossom in `blossoms`.
+    fn blossom_leaves_of(&self, blossoms: &[usize]) -> Vec<usize> {
+        blossoms
+            .iter()
+            .flat_map(|&b| self.blossom_leaves(b))
+            .collect()
+    }
+
+    /// Expands blossom `b` into its sub-blossoms, relabelling them if it was a T-blossom mid-stage.
+    fn expand_blossom(&mut self, b: usize, end_stage: bool) {
+        for child in self.blossom_children[b].clone() {
+            self.blossom_parent[child] = NONE;
+            if child < self.n_vertices {
+                self.in_blossom[child] = child;
+            } else if end_stage && self.dual[child] == 0. {
+                self.expand_blossom(child, end_stage);
+            } else {
+                for leaf in self.blossom_leaves(child) {
+                    self.in_blossom[leaf] = child;
+                }
+            }
+        }
+
+        if !end_stage && self.label[b] == 2 {
+            let children = self.blossom_children[b].clone();
+            let endpoints = self.blossom_endpoints[b].clone();
+            let len = children.len() as isize;
+            let at = |j: isize| j.rem_euclid(len) as usize;
+
+            let entry_child = self.in_blossom[self.endpoint[self.label_end[b] ^ 1]];
+            let mut j = children.iter().position(|&c| c == entry_child).unwrap() as isize;
+            let (j_step, endpoint_trick) = if j & 1 == 1 {
+                j -= len;
+                (1, 0usize)
+            } else {
+                (-1, 1)
+            };
+
+            // Relabel the T-sub-blossoms along the even-length path to the base.
+            let mut p = self.label_end[b];
+            while j != 0 {
+                self.label[self.endpoint[p ^ 1]] = 0;
+                let q = endpoints[at(j - endpoint_trick as isize)] ^ endpoint_trick ^ 1;
+                self.label[self.endpoint[q]] = 0;
+                self.assign_label(self.endpoint[p ^ 1], 2, p);
+                self.allow_edge[endpoints[at(j - endpoint_trick as isize)] / 2] = true;
+                j += j_step;
+                p = endpoints[at(j - endpoint_trick as isize)] ^ endpoint_trick;
+                self.allow_edge[p / 2] = true;
+                j += j_step;
+            }
+            let bv = children[at(j)];
+            self.label[self.endpoint[p ^ 1]] = 2;
+            self.label[bv] = 2;
+            self.label_end[self.endpoint[p ^ 1]] = p;
+            self.label_end[bv] = p;
+            self.best_edge[bv] = NONE;
+            j += j_step;
+
+            // Sub-blossoms on the odd-length path that are reachable become T again.
+            while children[at(j)] != entry_child {
+                let bv = children[at(j)];
+                if self.label[bv] == 1 {
+                    j += j_step;
+                    continue;
+                }
+                if let Some(v) = self
+                    .blossom_leaves(bv)
+                    .into_iter()
+                    .find(|&leaf| self.label[leaf] != 0)
+                {
+                    self.label[v] = 0;
+                    let mate = self.mate[self.blossom_base[bv]];
+                    self.label[self.endpoint[mate]] = 0;
+                    self.assign_label(v, 2, self.label_end[v]);
+                }
+                j += j_step;
+            }
+        }
+
+        self.label[b] = 0;
+        self.label_end[b] = NONE;
+        self.blossom_children[b].clear();
+        self.blossom_endpoints[b].clear();
+        self.blossom_base[b] = NONE;
+        self.blossom_best_edges[b] = None;
+        self.best_edge[b] = NONE;
+        self.unused_blossoms.push(b);
+    }
+
+    /// Swaps matched and unmatched edges inside blossom `b` so that vertex `v` becomes its base.
+    fn augment_blossom(&mut self, b: usize, v: usize) {
+        let mut t = v;
+        while self.blossom_parent[t] != b {
+            t = self.blossom_parent[t];
+        }
+        if t >= self.n_vertices {
+            self.augment_blossom(t, v);
+        }
+
+        let len = self.blossom_children[b].len() as isize;
+        let at = |j: isize| j.rem_euclid(len) as usize;
+        let i = self.blossom_children[b]
+            .iter()
+            .position(|&c| c == t)
+            .unwrap();
+        let mut j = i as isize;
+        let (j_step, endpoint_trick) = if j & 1 == 1 {
+            j -= len;
+            (1, 0usize)
+        } else {
+            (-1, 1)
+        };
+
+        while j != 0 {
+            j += j_step;
+            let t = self.blossom_children[b][at(j)];
+            let p = self.blossom_endpoints[b][at(j - endpoint_trick as isize)] ^ endpoint_trick;
+            if t >= self.n_vertices {
+                self.augment_blossom(t, self.endpoint[p]);
+            }
+            j += j_step;
+            let t = self.blossom_children[b][at(j)];
+            if t >= self.n_vertices {
+                self.augment_blossom(t, self.endpoint[p ^ 1]);
+            }
+            self.mate[self.endpoint[p]] = p ^ 1;
+            self.mate[self.endpoint[p ^ 1]] = p;
+        }
+
+        self.blossom_children[b].rotate_left(i);
+        self.blossom_endpoints[b].rotate_left(i);
+        self.blossom_base[b] = self.blossom_base[self.blossom_children[b][0]];
+    }
+
+    /// Augments the matching along the path through edge `k` between two S-vertices.
+    fn augment_matching(&mut self, k: usize) {
+        let (v, w, _) = self.edges[k];
+        for (mut s, mut p) in [(v, 2 * k + 1), (w, 2 * k)] {
+            loop {
+                let bs = self.in_blossom[s];
+                if bs >= self.n_vertices {
+                    self.augment_blossom(bs, s);
+                }
+                self.mate[s] = p;
+                if self.label_end[bs] == NONE {
+                    break;
+                }
+                let t = self.endpoint[self.label_end[bs]];
+                let bt = self.in_blossom[t];
+                s = self.endpoint[self.label_end[bt]];
+                let j = self.endpoint[self.label_end[bt] ^ 1];
+                if bt >= self.n_vertices {
+                    self.augment_blossom(bt, j);
+                }
+                self.mate[j] = self.label_end[bt];
+                p = self.label_end[bt] ^ 1;
+            }
+        }
+    }
+}
+
+/// Test that the matching pairs every odd node at minimum total distance.
+#[test]
+fn test_best_match_minimum_weight() {
+    use ndarray::array;
+    // Pairing 0-1 and 2-3 costs 2, the alternatives cost 10 and 20.
+    let distances = array![
+        [0.0, 1.0, 5.0, 10.0],
+        [1.0, 0.0, 10.0, 5.0],
+        [5.0, 10.0, 0.0, 1.0],
+        [10.0, 5.0, 1.0, 0.0]
+    ];
+    let mut matches: Vec<_> = best_match(&[0, 1, 2, 3], |i, j| distances[(i, j)], None)
+        .unwrap()
+        .into_iter()
+        .map(|Matching { from, to }| (from, to))
+        .collect();
+    matches.sort();
+    assert_eq!(matches, vec![(0, 1), (2, 3)]);
+}
+
+/// Test that the matching beats a greedy choice when an odd cycle forms a blossom.
+#[test]
+fn test_best_match_with_blossom() {
+    use crate::DEFAULT_COST_EPSILON;
+    let n = 6;
+    // Greedy picks the cheap edge 1-2 first and is then forced into expensive pairs.
+    let mut distances = ndarray::Array2::from_elem((n, n), 100.);
+    for (i, j, w) in [
+        (0, 1, 2.),
+        (1, 2, 1.),
+        (2, 3, 2.),
+        (0, 2, 3.),
+        (3, 4, 2.),
+        (4, 5, 1.),
+        (5, 3, 3.),
+    ] {
+        distances[(i, j)] = w;
+        distances[(j, i)] = w;
+    }
+    let matches = best_match(&(0..n).collect::<Vec<_>>(), |i, j| distances[(i, j)], None).unwrap();
+    let total: f64 = matches.iter().map(|m| distances[(m.from, m.to)]).sum();
+    assert_eq!(matches.len(), 3);
+    assert!((total - 5.).abs() <= DEFAULT_COST_EPSILON);
+}
+
+/// Test that the matching finds the cheapest pairing found by trying every one, on fractional
+/// distances.
+#[test]
+fn test_best_match_brute_force() {
+    use crate::DEFAULT_COST_EPSILON;
+    /// Returns the cost of the cheapest perfect matching of `nodes`.
+    fn cheapest(nodes: &[usize], distance: &impl Fn(usize, usize) -> f64) -> f64 {
+        let Some((&first, rest)) = nodes.split_first() else {
+            return 0.;
+        };
+        (0..rest.len())
+            .map(|i| {
+                let mut others = rest.to_vec();
+                let partner = others.remove(i);
+                distance(first, partner) + cheapest(&others, distance)
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    for seed in 1..20_u64 {
+        let n = 2 * (seed as usize % 4 + 1);
+        let mut state = seed;
+        let mut distances = ndarray::Array2::zeros((n, n));
+        for i in 0..n {
+            for j in i + 1..n {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let w = (state >> 40) as f64 / 1e4;
+                distances[(i, j)] = w;
+                distances[(j, i)] = w;
+            }
+        }
+        let distance = |i: usize, j: usize| distances[(i, j)];
+        let nodes: Vec<_> = (0..n).collect();
+        let matches = best_match(&nodes, distance, None).unwrap();
+        let mut matched: Vec<_> = matches.iter().flat_map(|m| [m.from, m.to]).collect();
+        matched.sort();
+        assert_eq!(matched, nodes);
+        let total: f64 = matches.iter().map(|m| distance(m.from, m.to)).sum();
+        let expected = cheapest(&nodes, &distance);
+        assert!((total - expected).abs() <= DEFAULT_COST_EPSILON * expected.max(1.));
+    }
+}
+
+/// Test that an empty list of odd nodes needs no pairs.
+#[test]
+fn test_best_match_no_odd_nodes() {
+    assert!(best_match(&[], |_, _| 1., None).unwrap().is_empty());
+}
+
+/// Test that a deadline passed before the matching gives up instead of finishing it.
+#[test]
+fn test_best_match_deadline() {
+    let distance = |i: usize, j: usize| i.abs_diff(j) as f64;
+    assert!(best_match(&[0, 1, 2, 3], distance, Some(Instant::now())).is_none());
+    let edges = [(0, 1, 1.0), (1, 2, 1.0), (2, 3, 1.0)];
+    assert!(BlossomMatcher::new(&edges)
+        .run(Some(Instant::now()))
+        .is_none());
+}
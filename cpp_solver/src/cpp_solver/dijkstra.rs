@@ -0,0 +1,191 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap},
+};
+
+use ndarray::Array2;
+
+use super::Graph;
+use crate::Weight;
+
+/// Represents a runner for Dijkstra's algorithm from a few source nodes.
+///
+/// Only the distances from the sources to the targets are kept, so memory grows with the
+/// number of sources instead of quadratically with the number of nodes. All weights must be
+/// non-negative, unless node potentials are given.
+pub struct DijkstraRunner<W> {
+    source_index: HashMap<usize, usize>,
+    target_index: HashMap<usize, usize>,
+    shortest_distances: Array2<Option<W>>,
+    predecessors: Vec<Vec<Option<usize>>>,
+}
+
+/// A heap entry ordered by distance, then by node.
+#[derive(PartialEq)]
+struct HeapEntry<W>(W, usize);
+
+impl<W: Weight> Eq for HeapEntry<W> {}
+
+impl<W: Weight> PartialOrd for HeapEntry<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<W: Weight> Ord for HeapEntry<W> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+impl<W: Weight> DijkstraRunner<W> {
+    /// Runs Dijkstra's algorithm from every source node.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to search. Its weights must be non-negative.
+    /// * `sources` - The nodes to start from. Duplicates are ignored.
+    /// * `targets` - The nodes whose distances are kept. Duplicates are ignored.
+    pub fn new(graph: &Graph<W>, sources: &[usize], targets: &[usize]) -> Self {
+        let potentials = vec![W::zero(); graph.weight_matrix().nrows()];
+        Self::with_potentials(graph, sources, targets, &potentials)
+    }
+
+    /// Runs Dijkstra's algorithm from every source node, on weights shifted by node potentials.
+    ///
+    /// Each edge `(u, v)` is searched with weight `w + potentials[u] - potentials[v]`, and the
+    /// shift is undone on the kept distances. This is how Johnson's algorithm handles negative
+    /// weights.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - The graph to search.
+    /// * `sources` - The nodes to start from. Duplicates are ignored.
+    /// * `targets` - The nodes whose distances are kept. Duplicates are ignored.
+    /// * `potentials` - Potentials making every shifted weight non-negative.
+    pub fn with_potentials(
+        graph: &Graph<W>,
+        sources: &[usize],
+        targets: &[usize],
+        potentials: &[W],
+    ) -> Self {
+        let source_index = Self::index(sources);
+        let target_index = Self::index(targets);
+        let mut shortest_distances =
+            Array2::from_elem((source_index.len(), target_index.len()), None);
+        let mut predecessors = vec![Vec::new(); source_index.len()];
+
+        for (&source, &i) in &source_index {
+            let (distances, source_predecessors) = Self::search(graph, source, potentials);
+            for (&target, &j) in &target_index {
+                shortest_distances[(i, j)] = distances[target]
+                    .map(|distance| distance - potentials[source] + potentials[target]);
+            }
+            predecessors[i] = source_predecessors;
+        }
+
+        Self {
+            source_index,
+            target_index,
+            shortest_distances,
+            predecessors,
+        }
+    }
+
+    /// Assigns a dense index to each distinct node.
+    fn index(nodes: &[usize]) -> HashMap<usize, usize> {
+        let mut index = HashMap::new();
+        for &node in nodes {
+            let next = index.len();
+            index.entry(node).or_insert(next);
+        }
+        index
+    }
+
+    /// Computes the shifted distances and predecessors of every node from a single source.
+    fn search(
+        graph: &Graph<W>,
+        source: usize,
+        potentials: &[W],
+    ) -> (Vec<Option<W>>, Vec<Option<usize>>) {
+        let weight_matrix = graph.weight_matrix();
+        let n_nodes = weight_matrix.nrows();
+        let mut distances = vec![None; n_nodes];
+        let mut predecessors = vec![None; n_nodes];
+        let mut heap = BinaryHeap::new();
+        distances[source] = Some(W::zero());
+        heap.push(Reverse(HeapEntry(W::zero(), source)));
+
+        while let Some(Reverse(HeapEntry(distance, node))) = heap.pop() {
+            if distances[node].is_some_and(|best| distance > best) {
+                continue; // Skip stale heap entries
+            }
+            for (next, &weight) in weight_matrix.row(node).indexed_iter() {
+                let Some(weight) = weight else {
+                    continue;
+                };
+                // Clamp rounding errors so shifted weights stay non-negative
+                let shifted = weight + potentials[node] - potentials[next];
+                let new_dist = if shifted < W::zero() {
+                    distance
+                } else {
+                    distance + shifted
+                };
+                if distances[next].is_none_or(|best| new_dist < best) {
+                    distances[next] = Some(new_dist);
+                    predecessors[next] = Some(node);
+                    heap.push(Reverse(HeapEntry(new_dist, next)));
+                }
+            }
+        }
+        (distances, predecessors)
+    }
+
+    /// Returns the shortest distance from a source node to a target node, or `None` if the
+    /// target is unreachable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` is not a source or `to` is not a target.
+    pub fn shortest_distance(&self, from: usize, to: usize) -> Option<W> {
+        self.shortest_distances[(self.source_index[&from], self.target_index[&to])]
+    }
+
+    /// Retrieves the shortest path from a source node to any node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is not a source.
+    pub fn shortest_path_between(&self, start: usize, end: usize) -> Vec<usize> {
+        let predecessors = &self.predecessors[self.source_index[&start]];
+        let mut path = vec![end];
+        let mut current_node = end;
+
+        while current_node != start {
+            match predecessors[current_node] {
+                Some(node) => current_node = node,
+                None => return Vec::new(), // Return empty path if no path exists
+            }
+            path.push(current_node);
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Test that the distances between sources and targets match the shortest paths.
+#[test]
+fn test_shortest_distance() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(1, 2, 1.0)
+        .add_edge(0, 2, 5.0)
+        .add_edge(2, 0, 1.0);
+    let graph = builder.build();
+    let runner = DijkstraRunner::new(&graph, &[0, 0], &[2, 0]);
+    assert_eq!(runner.shortest_distance(0, 2), Some(2.0));
+    assert_eq!(runner.shortest_distance(0, 0), Some(0.0));
+    assert_eq!(runner.shortest_path_between(0, 2), vec![0, 1, 2]);
+}
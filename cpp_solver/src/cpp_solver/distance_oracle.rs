@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use ndarray::ArrayView2;
+
+use super::floyd_warshall::FloydWarshallRunner;
+use crate::Weight;
+
+/// The all-pairs shortest paths of a graph, shared with the solver that computed them.
+///
+/// Returned by `CppSolver::precompute`. The solver and its clones solve with the same shortest
+/// paths, so the oracle can be kept and queried between solves from different start nodes
+/// without Floyd-Warshall running again. Cloning the oracle doesn't copy the paths.
+#[derive(Clone)]
+pub struct DistanceOracle<W = f64> {
+    pub(super) runner: Arc<FloydWarshallRunner<W>>,
+}
+
+impl<W: Weight> DistanceOracle<W> {
+    /// Returns the number of nodes of the graph the shortest paths were computed on.
+    pub fn node_count(&self) -> usize {
+        self.runner.shortest_distances().nrows()
+    }
+
+    /// Looks up the shortest distance between two nodes. A node is at distance zero from itself.
+    ///
+    /// # Returns
+    ///
+    /// The shortest distance, or `None` if either node doesn't exist or `to` is unreachable.
+    pub fn shortest_distance(&self, from: usize, to: usize) -> Option<W> {
+        if from == to && from < self.node_count() {
+            return Some(W::zero());
+        }
+        self.runner
+            .shortest_distances()
+            .get((from, to))
+            .copied()
+            .flatten()
+    }
+
+    /// Reconstructs a shortest path between two nodes. A path from a node to itself is just
+    /// that node.
+    ///
+    /// # Returns
+    ///
+    /// The nodes along the path, including both ends, or `None` if either node doesn't exist or
+    /// `to` is unreachable.
+    pub fn shortest_path_between(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let n_nodes = self.node_count();
+        if from >= n_nodes || to >= n_nodes {
+            return None;
+        }
+        if from == to {
+            return Some(vec![from]);
+        }
+        Some(self.runner.shortest_path_between(from, to)).filter(|path| !path.is_empty())
+    }
+
+    /// Returns the successor matrix, like `CppSolver::next_matrix`.
+    pub fn next_matrix(&self) -> ArrayView2<'_, Option<usize>> {
+        self.runner.next_matrix()
+    }
+}
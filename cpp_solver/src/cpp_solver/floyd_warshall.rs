@@ -1,65 +1,197 @@
+use std::time::Instant;
+
 use ndarray::{Array2, ArrayView2};
 
+use super::is_past;
+use crate::Weight;
+
 /// Represents a runner for the Floyd-Warshall algorithm.
-pub struct FloydWarshallRunner {
+#[derive(Clone)]
+pub struct FloydWarshallRunner<W> {
     n_nodes: usize,
-    shortest_distances: Array2<f64>,
+    shortest_distances: Array2<Option<W>>,
     next: Array2<Option<usize>>,
     have_negative_cycle: bool,
 }
 
-impl FloydWarshallRunner {
+impl<W: Weight> FloydWarshallRunner<W> {
     /// Initializes the Floyd-Warshall runner.
-    pub fn new(weight_matrix: Array2<f64>) -> Self {
+    ///
+    /// `weight_matrix[(i, j)]` is the weight of the edge from `i` to `j`, or `None` if there is none.
+    /// The matrix is only read, so the graph can keep sharing it. The runner fills its own buffer
+    /// with the direct edges and relaxes that in place into the shortest distances, which is the
+    /// only matrix of weights it allocates.
+    pub fn new(weight_matrix: ArrayView2<Option<W>>) -> Self {
+        Self::with_deadline(weight_matrix, None).expect("a run without a deadline always finishes")
+    }
+
+    /// Initializes the Floyd-Warshall runner, giving up once the deadline has passed.
+    ///
+    /// The deadline is checked before relaxing through each node, so the run may overrun it by
+    /// one quadratic pass.
+    ///
+    /// # Returns
+    ///
+    /// The runner, or `None` if the deadline passed before it finished.
+    pub fn with_deadline(
+        weight_matrix: ArrayView2<Option<W>>,
+        deadline: Option<Instant>,
+    ) -> Option<Self> {
+        let mut runner = Self::unsolved(weight_matrix);
+        #[cfg(not(feature = "parallel"))]
+        let finished = runner.find_shortest_distances(deadline);
+        #[cfg(feature = "parallel")]
+        let finished = runner.find_shortest_distances_parallel(deadline);
+        if !finished {
+            return None;
+        }
+        runner.detect_negative_cycles();
+        Some(runner)
+    }
+
+    /// Sets up the runner with the direct edges only, before any relaxation.
+    fn unsolved(weight_matrix: ArrayView2<Option<W>>) -> Self {
         let n_nodes = weight_matrix.nrows();
         let next = Array2::from_shape_fn((n_nodes, n_nodes), |(i, j)| {
-            if weight_matrix[(i, j)] < f64::INFINITY {
-                Some(j)
-            } else {
-                None
-            }
+            weight_matrix[(i, j)].map(|_| j)
         });
 
-        let mut runner = Self {
+        Self {
             n_nodes,
-            shortest_distances: weight_matrix,
+            shortest_distances: weight_matrix.to_owned(),
             next,
             have_negative_cycle: false,
-        };
-
-        runner.find_shortest_distances();
-        runner.detect_negative_cycles();
-        runner
+        }
     }
 
     /// Optimized calculation of shortest distances using Floyd-Warshall.
-    fn find_shortest_distances(&mut self) {
+    ///
+    /// A path whose length overflows the weight type is never taken, so its ends stay
+    /// unreachable if there is no shorter path between them.
+    ///
+    /// # Returns
+    ///
+    /// `true` if every node was relaxed through before the deadline passed.
+    #[cfg(any(not(feature = "parallel"), test))]
+    fn find_shortest_distances(&mut self, deadline: Option<Instant>) -> bool {
         for k in 0..self.n_nodes {
+            if is_past(deadline) {
+                return false;
+            }
             for i in 0..self.n_nodes {
-                if self.shortest_distances[(i, k)] == f64::INFINITY {
+                let Some(to_k) = self.shortest_distances[(i, k)] else {
                     continue; // Skip unreachable intermediates
-                }
+                };
 
                 for j in 0..self.n_nodes {
-                    if self.shortest_distances[(k, j)] == f64::INFINITY {
+                    let Some(from_k) = self.shortest_distances[(k, j)] else {
                         continue; // Skip unreachable destinations
-                    }
+                    };
 
-                    let new_dist =
-                        self.shortest_distances[(i, k)] + self.shortest_distances[(k, j)];
-                    if new_dist < self.shortest_distances[(i, j)] {
-                        self.shortest_distances[(i, j)] = new_dist;
+                    let Some(new_dist) = to_k.checked_add(from_k) else {
+                        continue; // Skip paths too long to measure
+                    };
+                    if self.shortest_distances[(i, j)].is_none_or(|dist| new_dist < dist) {
+                        self.shortest_distances[(i, j)] = Some(new_dist);
                         self.next[(i, j)] = self.next[(i, k)];
                     }
                 }
             }
         }
+        true
+    }
+
+    /// Calculation of shortest distances using Floyd-Warshall, relaxing the rows in parallel.
+    ///
+    /// Row `k` and column `k` don't change while relaxing through `k`, so each row `i` can be
+    /// updated on its own from a copy of row `k`.
+    #[cfg(feature = "parallel")]
+    fn find_shortest_distances_parallel(&mut self, deadline: Option<Instant>) -> bool {
+        use ndarray::Zip;
+
+        for k in 0..self.n_nodes {
+            if is_past(deadline) {
+                return false;
+            }
+            let through_k = self.shortest_distances.row(k).to_owned();
+            Zip::from(self.shortest_distances.rows_mut())
+                .and(self.next.rows_mut())
+                .par_for_each(|mut distances, mut next| {
+                    let Some(to_k) = distances[k] else {
+                        return; // Skip unreachable intermediates
+                    };
+                    let next_k = next[k];
+
+                    for (j, &from_k) in through_k.iter().enumerate() {
+                        let Some(from_k) = from_k else {
+                            continue; // Skip unreachable destinations
+                        };
+
+                        let Some(new_dist) = to_k.checked_add(from_k) else {
+                            continue; // Skip paths too long to measure
+                        };
+                        if distances[j].is_none_or(|dist| new_dist < dist) {
+                            distances[j] = Some(new_dist);
+                            next[j] = next_k;
+                        }
+                    }
+                });
+        }
+        true
+    }
+
+    /// Updates the shortest paths after an edge is added to the graph, in quadratic time.
+    ///
+    /// A shortest path walks the new edge at most once, so each pair only needs to be compared
+    /// with the path going through it.
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: W) {
+        // Take the distances to and from the new edge before any of them change
+        let to_edge: Vec<_> = (0..self.n_nodes)
+            .map(|i| {
+                if i == from {
+                    Some((W::zero(), to))
+                } else {
+                    self.shortest_distances[(i, from)].zip(self.next[(i, from)])
+                }
+            })
+            .collect();
+        let from_edge: Vec<_> = (0..self.n_nodes)
+            .map(|j| {
+                if j == to {
+                    Some(W::zero())
+                } else {
+                    self.shortest_distances[(to, j)]
+                }
+            })
+            .collect();
+
+        for (i, &to_edge) in to_edge.iter().enumerate() {
+            let Some((to_from, first_hop)) = to_edge else {
+                continue; // Skip nodes that can't reach the new edge
+            };
+            for (j, &from_to) in from_edge.iter().enumerate() {
+                let Some(from_to) = from_to else {
+                    continue; // Skip nodes the new edge can't reach
+                };
+                let Some(new_dist) = to_from
+                    .checked_add(weight)
+                    .and_then(|dist| dist.checked_add(from_to))
+                else {
+                    continue; // Skip paths too long to measure
+                };
+                if self.shortest_distances[(i, j)].is_none_or(|dist| new_dist < dist) {
+                    self.shortest_distances[(i, j)] = Some(new_dist);
+                    self.next[(i, j)] = Some(first_hop);
+                }
+            }
+        }
+        self.detect_negative_cycles();
     }
 
     /// Detects negative cycles in the graph.
     fn detect_negative_cycles(&mut self) {
         for i in 0..self.n_nodes {
-            if self.shortest_distances[(i, i)] < 0.0 {
+            if self.shortest_distances[(i, i)].is_some_and(|dist| dist < W::zero()) {
                 self.have_negative_cycle = true;
                 return;
             }
@@ -67,11 +199,18 @@ impl FloydWarshallRunner {
     }
 
     /// Retrieves the shortest path between two nodes.
+    ///
+    /// Negative edges are fine, but on a negative cycle the successors may loop forever. A
+    /// shortest path visits each node at most once, so a chain longer than that yields an empty
+    /// path as well.
     pub fn shortest_path_between(&self, start: usize, end: usize) -> Vec<usize> {
         let mut path = Vec::new();
         let mut current_node = Some(start);
 
         while let Some(node) = current_node {
+            if path.len() == self.n_nodes {
+                return Vec::new(); // The successors loop through a negative cycle
+            }
             path.push(node);
             if node == end {
                 break;
@@ -87,18 +226,42 @@ impl FloydWarshallRunner {
     }
 
     /// Returns a view of the shortest distances matrix.
-    pub fn shortest_distances(&self) -> ArrayView2<f64> {
+    ///
+    /// Unreachable pairs are `None`.
+    pub fn shortest_distances(&self) -> ArrayView2<'_, Option<W>> {
         self.shortest_distances.view()
     }
 
+    /// Returns a view of the successor matrix.
+    ///
+    /// `next[(i, j)]` is the first hop on a shortest path from `i` to `j`, or `None` if `j` is
+    /// unreachable from `i`. Following it repeatedly from `i` reconstructs the whole path.
+    pub fn next_matrix(&self) -> ArrayView2<'_, Option<usize>> {
+        self.next.view()
+    }
+
     /// Checks if the graph has no negative cycle.
     pub fn graph_has_no_negative_cycle(&self) -> bool {
         !self.have_negative_cycle
     }
 
-    /// Checks if the graph is strongly connected.
-    pub fn graph_is_strongly_connected(&self) -> bool {
-        self.shortest_distances.iter().all(|&x| x != f64::INFINITY)
+    /// Checks if the graph would be strongly connected after adding an edge.
+    pub fn graph_is_strongly_connected_with(&self, from: usize, to: usize) -> bool {
+        let reaches = |i: usize, j: usize| i == j || self.shortest_distances[(i, j)].is_some();
+        self.shortest_distances
+            .indexed_iter()
+            .all(|((i, j), x)| x.is_some() || (reaches(i, from) && reaches(to, j)))
+    }
+
+    /// Checks if the graph would still have no negative cycle after adding an edge.
+    pub fn graph_has_no_negative_cycle_with(&self, from: usize, to: usize, weight: W) -> bool {
+        let back = if from == to {
+            Some(W::zero())
+        } else {
+            self.shortest_distances[(to, from)]
+        };
+        // Without a way back, the new edge can't close a cycle
+        self.graph_has_no_negative_cycle() && back.is_none_or(|back| back + weight >= W::zero())
     }
 }
 
@@ -108,19 +271,19 @@ fn test_shortest_path_between() {
     let weight_matrix = Array2::from_shape_vec(
         (3, 3),
         vec![
-            0.0,
-            1.0,
-            f64::INFINITY,
-            f64::INFINITY,
-            0.0,
-            1.0,
-            1.0,
-            f64::INFINITY,
-            0.0,
+            Some(0.0),
+            Some(1.0),
+            None,
+            None,
+            Some(0.0),
+            Some(1.0),
+            Some(1.0),
+            None,
+            Some(0.0),
         ],
     )
     .unwrap();
-    let runner = FloydWarshallRunner::new(weight_matrix);
+    let runner = FloydWarshallRunner::new(weight_matrix.view());
     let path = runner.shortest_path_between(0, 2);
     assert_eq!(path, vec![0, 1, 2]);
 }
@@ -128,7 +291,134 @@ fn test_shortest_path_between() {
 /// Test that the algorithm detects negative cycles in the graph.
 #[test]
 fn test_graph_has_no_negative_cycle() {
-    let weight_matrix = Array2::from_shape_vec((2, 2), vec![0.0, -1.0, -1.0, 0.0]).unwrap();
-    let runner = FloydWarshallRunner::new(weight_matrix);
+    let weight_matrix =
+        Array2::from_shape_vec((2, 2), vec![Some(0.0), Some(-1.0), Some(-1.0), Some(0.0)]).unwrap();
+    let runner = FloydWarshallRunner::new(weight_matrix.view());
     assert!(!runner.graph_has_no_negative_cycle());
 }
+
+/// Test that reconstructing a path through a negative cycle stops instead of looping.
+#[test]
+fn test_shortest_path_between_negative_cycle() {
+    let weight_matrix = Array2::from_shape_vec(
+        (3, 3),
+        vec![
+            None,
+            Some(1.0),
+            None,
+            Some(-2.0),
+            None,
+            Some(1.0),
+            None,
+            None,
+            None,
+        ],
+    )
+    .unwrap();
+    let runner = FloydWarshallRunner::new(weight_matrix.view());
+    assert!(!runner.graph_has_no_negative_cycle());
+    assert!(runner.shortest_path_between(0, 2).len() <= 3);
+}
+
+/// Test that paths too long for `f64` are left unreachable instead of infinitely long.
+#[test]
+fn test_overflowing_distances() {
+    let half = f64::MAX / 2.0;
+    let weight_matrix = Array2::from_shape_fn((4, 4), |(i, j)| (j == i + 1).then_some(half));
+    let runner = FloydWarshallRunner::new(weight_matrix.view());
+    let distances = runner.shortest_distances();
+    assert_eq!(distances[(0, 2)], Some(f64::MAX));
+    assert_eq!(distances[(0, 3)], None);
+    assert!(runner.shortest_path_between(0, 3).is_empty());
+    assert!(distances.iter().flatten().all(|dist| dist.is_finite()));
+}
+
+/// Test that connectivity is predicted correctly for a candidate edge.
+#[test]
+fn test_graph_is_strongly_connected_with() {
+    let weight_matrix = Array2::from_shape_vec(
+        (3, 3),
+        vec![
+            None,
+            Some(1.0),
+            None,
+            None,
+            None,
+            Some(1.0),
+            None,
+            None,
+            None,
+        ],
+    )
+    .unwrap();
+    let runner = FloydWarshallRunner::new(weight_matrix.view());
+    assert!(runner.shortest_distances()[(2, 0)].is_none());
+    assert!(runner.graph_is_strongly_connected_with(2, 0));
+    assert!(!runner.graph_is_strongly_connected_with(1, 0));
+    assert!(runner.graph_has_no_negative_cycle_with(2, 0, -1.0));
+    assert!(!runner.graph_has_no_negative_cycle_with(2, 0, -3.0));
+}
+
+/// Test that the successor matrix points to the first hop of each shortest path.
+#[test]
+fn test_next_matrix() {
+    let weight_matrix = Array2::from_shape_vec(
+        (3, 3),
+        vec![
+            None,
+            Some(1.0),
+            Some(5.0),
+            None,
+            None,
+            Some(1.0),
+            Some(1.0),
+            None,
+            None,
+        ],
+    )
+    .unwrap();
+    let runner = FloydWarshallRunner::new(weight_matrix.view());
+    let next = runner.next_matrix();
+    assert_eq!(next[(0, 2)], Some(1));
+    assert_eq!(next[(1, 2)], Some(2));
+    assert_eq!(next[(1, 0)], Some(2));
+}
+
+/// Test that the parallel and sequential runs agree on a 500-node random graph.
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_matches_sequential() {
+    let n_nodes = 500;
+    let mut seed: u64 = 42;
+    let mut random = move || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        seed >> 33
+    };
+    let weight_matrix = Array2::from_shape_fn((n_nodes, n_nodes), |_| {
+        if random() % 20 == 0 {
+            Some((random() % 100 + 1) as f64)
+        } else {
+            None
+        }
+    });
+
+    let parallel = FloydWarshallRunner::new(weight_matrix.view());
+    let mut sequential = FloydWarshallRunner::unsolved(weight_matrix.view());
+    assert!(sequential.find_shortest_distances(None));
+
+    assert_eq!(parallel.shortest_distances, sequential.shortest_distances);
+    assert_eq!(parallel.next, sequential.next);
+}
+
+/// Test that a run past its deadline gives up instead of returning partial distances.
+#[test]
+fn test_deadline() {
+    let weight_matrix = ndarray::arr2(&[[None, Some(1.0)], [Some(1.0), None]]);
+    let past = Instant::now();
+    assert!(FloydWarshallRunner::with_deadline(weight_matrix.view(), Some(past)).is_none());
+    let future = past + std::time::Duration::from_secs(60);
+    let runner = FloydWarshallRunner::with_deadline(weight_matrix.view(), Some(future)).unwrap();
+    assert_eq!(runner.shortest_distances[(0, 1)], Some(1.0));
+}
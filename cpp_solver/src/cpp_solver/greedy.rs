@@ -0,0 +1,49 @@
+use ordered_float::OrderedFloat;
+
+use super::hungarian::Matching;
+use crate::graph::ImbalancedNodeSet;
+
+/// Pairs each node of negative imbalance with the nearest node of positive imbalance that is
+/// not paired yet, in node order.
+///
+/// # Arguments
+///
+/// * `imbalanced_nodes` - The set of imbalanced nodes.
+/// * `shortest_distance` - Returns the shortest distance between two nodes.
+///
+/// # Returns
+///
+/// A vector of `Matching` structs, one per node of negative imbalance.
+pub(super) fn nearest_match(
+    imbalanced_nodes: &ImbalancedNodeSet,
+    shortest_distance: impl Fn(usize, usize) -> f64,
+) -> Vec<Matching> {
+    let mut unpaired: Vec<usize> = imbalanced_nodes.positive.clone();
+    imbalanced_nodes
+        .negative
+        .iter()
+        .map(|&from| {
+            let nearest = (0..unpaired.len())
+                .min_by_key(|&i| OrderedFloat(shortest_distance(from, unpaired[i])))
+                .expect("every imbalance has an opposite imbalance");
+            Matching {
+                from,
+                to: unpaired.remove(nearest),
+            }
+        })
+        .collect()
+}
+
+/// Test that each node is paired with its nearest unpaired node, even when a better total exists.
+#[test]
+fn test_nearest_match() {
+    let imbalanced_nodes = ImbalancedNodeSet {
+        negative: vec![0, 1],
+        positive: vec![2, 3],
+    };
+    let distances = [[0.0, 0.0, 1.0, 2.0], [0.0, 0.0, 1.0, 10.0]];
+    let matching = nearest_match(&imbalanced_nodes, |from, to| distances[from][to]);
+    let pairs: Vec<_> = matching.iter().map(|m| (m.from, m.to)).collect();
+    // The optimal pairing would be (0, 3) and (1, 2), with total 3 instead of 11
+    assert_eq!(pairs, [(0, 2), (1, 3)]);
+}
@@ -1,16 +1,26 @@
 use std::collections::VecDeque;
 
-use super::Graph;
+use super::{Graph, SolveError};
+use crate::Weight;
 
-pub(super) struct HierholzerRunner {
+#[derive(Clone)]
+pub(super) struct HierholzerRunner<W> {
     path: VecDeque<usize>,
+    weights: VecDeque<W>,
+    /// Whether to walk to the smallest neighbour first, instead of the last edge added.
+    canonical: bool,
 }
 
-impl HierholzerRunner {
-    /// Creates a new instance of `HierholzerRunner`.
-    pub fn new() -> Self {
+impl<W: Weight> HierholzerRunner<W> {
+    /// Creates a new instance of `HierholzerRunner`, choosing the order neighbours are walked in.
+    ///
+    /// Walking to the smallest neighbour first, and to the cheaper of parallel edges, yields the
+    /// lexicographically smallest circuit from the start node.
+    pub fn with_canonical_order(canonical: bool) -> Self {
         Self {
             path: VecDeque::new(),
+            weights: VecDeque::new(),
+            canonical,
         }
     }
 
@@ -18,29 +28,136 @@ impl HierholzerRunner {
     ///
     /// # Arguments
     ///
-    /// * `graph` - A reference to the graph. The graph must be Eulerian.
+    /// * `graph` - A reference to the graph. It must be balanced, or have an Euler path from
+    ///   `start`, and every edge must be reachable from `start`.
+    /// * `start` - The node the circuit starts and ends at. It must have an outgoing edge.
     ///
     /// # Returns
     ///
-    /// `Ok(())` if the path is found, or an error message if the graph is not Eulerian.
-    pub fn run(&mut self, graph: &Graph) {
-        if !Self::is_eulerian(graph) {
-            panic!("The graph is not Eulerian.");
+    /// `Ok(())` if the path is found, `SolveError::NotEulerianAfterBalancing` with the edges that
+    /// can't be walked if the graph has no Euler path from `start`, or
+    /// `SolveError::InvalidStartNode` if the start node has no outgoing edge.
+    pub fn run(&mut self, graph: &Graph<W>, start: usize) -> Result<(), SolveError> {
+        if !Self::has_euler_path_from(graph, start) {
+            return Err(SolveError::NotEulerianAfterBalancing {
+                uncovered: graph.unbalanced_edges(),
+            });
+        }
+        if graph
+            .out_degrees()
+            .get(start)
+            .is_none_or(|&out_degree| out_degree == 0)
+        {
+            return Err(SolveError::InvalidStartNode(start));
         }
 
-        let mut edge_set = graph.edge_set(); // Clone edge set
-        let mut out_degrees = graph.out_degrees().to_vec(); // Clone out-degrees
+        self.clear();
+        let mut edge_set = graph.weighted_edge_set(); // Clone edge set
+        if self.canonical {
+            // Edges are popped from the back, so the smallest neighbour goes last
+            for edges in &mut edge_set {
+                edges.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.total_cmp(&a.1)));
+            }
+        }
 
-        self.find_path(0, &mut edge_set, &mut out_degrees);
+        self.find_path(start, &mut edge_set);
+        // Edges left over are out of reach of the start node
+        let uncovered: Vec<(usize, usize)> = edge_set
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |&(to, _)| (from, to)))
+            .collect();
+        if !uncovered.is_empty() {
+            return Err(SolveError::NotEulerianAfterBalancing { uncovered });
+        }
+        Ok(())
     }
 
-    /// Validates if a graph is Eulerian.
-    fn is_eulerian(graph: &Graph) -> bool {
-        graph
-            .out_degrees()
+    /// Runs the algorithm to find an Euler circuit in a graph read as undirected.
+    ///
+    /// # Arguments
+    ///
+    /// * `graph` - A reference to the graph. Every node must have an even undirected degree.
+    /// * `start` - The node the circuit starts and ends at. It must have an incident edge.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the path is found, or an error if some node has an odd degree or the start
+    /// node has no incident edge.
+    pub fn run_undirected(&mut self, graph: &Graph<W>, start: usize) -> Result<(), SolveError> {
+        if !graph.odd_degree_nodes().is_empty() {
+            return Err(SolveError::NotEulerianAfterBalancing {
+                uncovered: graph.odd_degree_edges(),
+            });
+        }
+        let (mut edge_set, n_edges) = graph.undirected_edge_set();
+        if edge_set.get(start).is_none_or(Vec::is_empty) {
+            return Err(SolveError::InvalidStartNode(start));
+        }
+
+        self.clear();
+        if self.canonical {
+            // Edges are popped from the back, so the smallest neighbour goes last
+            for edges in &mut edge_set {
+                edges.sort_by(|a, b| b.0.cmp(&a.0).then(b.2.total_cmp(&a.2)));
+            }
+        }
+        let mut used = vec![false; n_edges];
+        let mut stack = vec![(start, None)];
+
+        while let Some((node, reached_by)) = stack.pop() {
+            // Skip edges already walked from their other end.
+            while edge_set[node]
+                .last()
+                .is_some_and(|&(_, edge, _)| used[edge])
+            {
+                edge_set[node].pop();
+            }
+            match edge_set[node].pop() {
+                Some((next_node, edge, weight)) => {
+                    used[edge] = true;
+                    stack.push((node, reached_by));
+                    stack.push((next_node, Some(weight)));
+                }
+                None => self.push_front((node, reached_by)),
+            }
+        }
+        // Streets left over are out of reach of the start node. Each is listed at both ends, so
+        // it is read from its lower end and reported in both directions.
+        let uncovered: Vec<(usize, usize)> = edge_set
             .iter()
-            .zip(graph.edge_set().iter())
-            .all(|(out, edges)| edges.len() == *out)
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |&(to, edge, _)| (from, to, edge)))
+            .filter(|&(from, to, edge)| from <= to && !used[edge])
+            .flat_map(|(from, to, _)| {
+                if from == to {
+                    vec![(from, to)]
+                } else {
+                    vec![(from, to), (to, from)]
+                }
+            })
+            .collect();
+        if !uncovered.is_empty() {
+            return Err(SolveError::NotEulerianAfterBalancing { uncovered });
+        }
+        Ok(())
+    }
+
+    /// Forgets the path of a previous run, so the runner can be reused.
+    fn clear(&mut self) {
+        self.path.clear();
+        self.weights.clear();
+    }
+
+    /// Checks if the degrees allow a path walking every edge once from the start node.
+    ///
+    /// Either every node is balanced and the path is a circuit, or only the start node has one
+    /// more outgoing than incoming edge and the path ends at the one node with the opposite
+    /// imbalance.
+    fn has_euler_path_from(graph: &Graph<W>, start: usize) -> bool {
+        let imbalanced_nodes = graph.imbalanced_nodes();
+        imbalanced_nodes.is_empty()
+            || (imbalanced_nodes.positive == [start] && imbalanced_nodes.negative.len() == 1)
     }
 
     /// Retrieves the Eulerian path or circuit.
@@ -48,26 +165,38 @@ impl HierholzerRunner {
         self.path.clone()
     }
 
+    /// Retrieves the weight of each edge walked by the path, in order.
+    ///
+    /// Parallel edges between the same nodes keep their own weights.
+    pub fn weights(&self) -> Vec<W> {
+        self.weights.iter().cloned().collect()
+    }
+
     /// Finds the Eulerian path or circuit using an iterative DFS approach.
-    fn find_path(
-        &mut self,
-        start_node: usize,
-        edge_set: &mut [Vec<usize>],
-        out_degrees: &mut [usize],
-    ) {
-        let mut stack = Vec::new();
-        stack.push(start_node);
-
-        while let Some(node) = stack.last() {
-            if out_degrees[*node] > 0 {
-                out_degrees[*node] -= 1;
-                let next_node = edge_set[*node].pop().unwrap();
-                stack.push(next_node);
-            } else {
-                self.path.push_front(stack.pop().unwrap());
+    ///
+    /// Each stack entry holds a node and the weight of the edge used to reach it. Walked edges
+    /// are removed from `edge_set`, so it only keeps the edges the path never reached.
+    fn find_path(&mut self, start_node: usize, edge_set: &mut [Vec<(usize, W)>]) {
+        let mut stack = vec![(start_node, None)];
+
+        while let Some((node, reached_by)) = stack.pop() {
+            match edge_set[node].pop() {
+                Some((next_node, weight)) => {
+                    stack.push((node, reached_by));
+                    stack.push((next_node, Some(weight)));
+                }
+                None => self.push_front((node, reached_by)),
             }
         }
     }
+
+    /// Prepends a finished node, and the weight of the edge that reached it, to the path.
+    fn push_front(&mut self, (node, weight): (usize, Option<W>)) {
+        self.path.push_front(node);
+        if let Some(weight) = weight {
+            self.weights.push_front(weight);
+        }
+    }
 }
 
 /// Test that Hierholzer's algorithm finds a simple cycle correctly.
@@ -77,10 +206,117 @@ fn test_hierholzer_simple_cycle() {
     let mut builder = GraphBuilder::new();
     builder.add_edge(0, 1, 1.0).add_edge(1, 0, 1.0);
     let graph = builder.build();
-    let mut runner = HierholzerRunner::new();
-    runner.run(&graph);
+    let mut runner = HierholzerRunner::with_canonical_order(false);
+    runner.run(&graph, 0).unwrap();
     assert_eq!(
         runner.path().iter().cloned().collect::<Vec<_>>(),
         vec![0, 1, 0]
     );
 }
+
+/// Test that the circuit can start from any node with outgoing edges.
+#[test]
+fn test_hierholzer_start_node() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(1, 2, 1.0)
+        .add_edge(2, 0, 1.0);
+    let graph = builder.build();
+    let mut runner = HierholzerRunner::with_canonical_order(false);
+    runner.run(&graph, 2).unwrap();
+    assert_eq!(
+        runner.path().iter().cloned().collect::<Vec<_>>(),
+        vec![2, 0, 1, 2]
+    );
+}
+
+/// Test that a lone self-loop is walked once.
+#[test]
+fn test_hierholzer_self_loop() {
+    let graph = Graph::from_adjacency_list(vec![vec![(0, 1.0)]], None);
+    let mut runner = HierholzerRunner::with_canonical_order(false);
+    runner.run(&graph, 0).unwrap();
+    assert_eq!(runner.path(), [0, 0]);
+    runner.run_undirected(&graph, 0).unwrap();
+    assert_eq!(runner.path(), [0, 0]);
+}
+
+/// Test that a start node without edges is rejected instead of yielding a one-node path.
+#[test]
+fn test_hierholzer_isolated_start_node() {
+    let graph = Graph::from_adjacency_list(vec![vec![(0, 1.0)], vec![]], None);
+    let mut runner = HierholzerRunner::with_canonical_order(false);
+    assert_eq!(runner.run(&graph, 1), Err(SolveError::InvalidStartNode(1)));
+    assert_eq!(runner.run(&graph, 2), Err(SolveError::InvalidStartNode(2)));
+    assert_eq!(
+        runner.run_undirected(&graph, 1),
+        Err(SolveError::InvalidStartNode(1))
+    );
+}
+
+/// Test that an odd-degree graph is rejected with the edges at its odd nodes.
+#[test]
+fn test_hierholzer_odd_degree_uncovered_edges() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_undirected_edge(0, 1, 1.0)
+        .add_undirected_edge(1, 2, 1.0)
+        .add_undirected_edge(2, 0, 1.0)
+        .add_undirected_edge(2, 3, 1.0);
+    let graph = builder.build();
+    let mut runner = HierholzerRunner::with_canonical_order(false);
+    assert_eq!(
+        runner.run_undirected(&graph, 0),
+        Err(SolveError::NotEulerianAfterBalancing {
+            uncovered: vec![(1, 2), (2, 1), (2, 0), (0, 2), (2, 3), (3, 2)]
+        })
+    );
+}
+
+/// Test that an unbalanced graph is rejected instead of yielding a broken path.
+#[test]
+fn test_hierholzer_unbalanced_graph() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder.add_edge(0, 1, 1.0).add_edge(0, 2, 1.0);
+    let graph = builder.build();
+    let mut runner = HierholzerRunner::with_canonical_order(false);
+    assert_eq!(
+        runner.run(&graph, 0),
+        Err(SolveError::NotEulerianAfterBalancing {
+            uncovered: vec![(0, 1), (0, 2)]
+        })
+    );
+    // The only node with an outgoing surplus may start an open path.
+    let graph = GraphBuilder::from(vec![(0, 1, 1.0), (1, 2, 1.0)]).build();
+    runner.run(&graph, 0).unwrap();
+    assert_eq!(runner.path(), [0, 1, 2]);
+    assert!(runner.run(&graph, 1).is_err());
+}
+
+/// Test that edges out of reach of the start node are reported instead of silently skipped.
+#[test]
+fn test_hierholzer_unreachable_edges() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_undirected_edge(0, 1, 1.0)
+        .add_undirected_edge(1, 2, 1.0)
+        .add_undirected_edge(2, 0, 1.0)
+        .add_undirected_edge(3, 4, 1.0)
+        .add_undirected_edge(4, 5, 1.0)
+        .add_undirected_edge(5, 3, 1.0);
+    let graph = builder.build();
+    let mut runner = HierholzerRunner::with_canonical_order(false);
+    let expected = [(3, 4), (3, 5), (4, 3), (4, 5), (5, 3), (5, 4)];
+    for result in [runner.run(&graph, 0), runner.run_undirected(&graph, 0)] {
+        let Err(SolveError::NotEulerianAfterBalancing { mut uncovered }) = result else {
+            panic!("expected uncovered edges, found {:?}", result);
+        };
+        uncovered.sort();
+        assert_eq!(uncovered, expected);
+    }
+}
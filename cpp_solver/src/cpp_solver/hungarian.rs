@@ -1,7 +1,9 @@
-use ndarray::ArrayView2;
+use std::time::Instant;
+
 use ordered_float::OrderedFloat;
 use pathfinding::{kuhn_munkres::kuhn_munkres_min, prelude::Matrix};
 
+use super::is_past;
 use crate::graph::ImbalancedNodeSet;
 
 /// Represents a matching between two nodes.
@@ -13,29 +15,50 @@ pub(super) struct Matching {
 
 /// Finds the best match between imbalanced nodes based on the shortest distance between them.
 ///
+/// Every graph has as many nodes of negative as of positive imbalance. If the counts differ
+/// anyway, the nodes on the longer side that are the most costly to match are left unmatched.
+///
 /// # Arguments
 ///
 /// * `imbalanced_nodes` - The set of imbalanced nodes.
-/// * `shortest_distance_between_nodes` - The shortest distance between nodes represented as a 2D array.
+/// * `shortest_distance` - Returns the shortest distance between two nodes.
+/// * `deadline` - The time by which the distance matrix must be built. It is checked before
+///   each row, but the Kuhn-Munkres call itself can't be interrupted, so the caller checks the
+///   deadline again once the matching is found.
 ///
 /// # Returns
 ///
-/// A vector of `Matching` structs representing the best match between imbalanced nodes.
+/// A vector of `Matching` structs representing the best match between imbalanced nodes, one
+/// per node on the shorter side, or `None` if the deadline passed.
 pub(super) fn best_match(
     imbalanced_nodes: &ImbalancedNodeSet,
-    shortest_distance_between_nodes: ArrayView2<f64>,
-) -> Vec<Matching> {
-    let weights = shortest_distances_between_imbalanced_nodes(
-        imbalanced_nodes,
-        shortest_distance_between_nodes,
-    );
+    shortest_distance: impl Fn(usize, usize) -> f64 + Sync,
+    deadline: Option<Instant>,
+) -> Option<Vec<Matching>> {
+    let weights =
+        shortest_distances_between_imbalanced_nodes(imbalanced_nodes, shortest_distance, deadline)?;
+    // Kuhn-Munkres needs at least as many columns as rows, so match the other way around
+    // when there are more nodes of negative imbalance.
+    if weights.rows > weights.columns {
+        let (_, best_match) = kuhn_munkres_min(&weights.transposed());
+        return Some(
+            imbalanced_nodes
+                .positive
+                .iter()
+                .zip(best_match.iter().map(|&x| imbalanced_nodes.negative[x]))
+                .map(|(&to, from)| Matching { from, to })
+                .collect(),
+        );
+    }
     let (_, best_match) = kuhn_munkres_min(&weights);
-    imbalanced_nodes
-        .negative
-        .iter()
-        .zip(best_match.iter().map(|&x| imbalanced_nodes.positive[x]))
-        .map(|(&from, to)| Matching { from, to })
-        .collect()
+    Some(
+        imbalanced_nodes
+            .negative
+            .iter()
+            .zip(best_match.iter().map(|&x| imbalanced_nodes.positive[x]))
+            .map(|(&from, to)| Matching { from, to })
+            .collect(),
+    )
 }
 
 /// Calculates the shortest distances between imbalanced nodes based on the shortest distance between all nodes.
@@ -43,22 +66,138 @@ pub(super) fn best_match(
 /// # Arguments
 ///
 /// * `imbalanced_nodes` - The set of imbalanced nodes.
-/// * `shortest_distance_between_nodes` - The shortest distance between nodes represented as a 2D array.
+/// * `shortest_distance` - Returns the shortest distance between two nodes.
+/// * `deadline` - The time by which the matrix must be built, checked before each row.
 ///
 /// # Returns
 ///
-/// A matrix representing the shortest distances between imbalanced nodes.
+/// A matrix representing the shortest distances between imbalanced nodes, or `None` if the
+/// deadline passed.
 fn shortest_distances_between_imbalanced_nodes(
     imbalanced_nodes: &ImbalancedNodeSet,
-    shortest_distance_between_nodes: ArrayView2<f64>,
-) -> Matrix<OrderedFloat<f64>> {
-    Matrix::from_fn(
-        imbalanced_nodes.negative.len(),
-        imbalanced_nodes.positive.len(),
-        |(i, j)| {
-            let from = imbalanced_nodes.negative[i];
-            let to = imbalanced_nodes.positive[j];
-            OrderedFloat(shortest_distance_between_nodes[(from, to)])
-        },
+    shortest_distance: impl Fn(usize, usize) -> f64 + Sync,
+    deadline: Option<Instant>,
+) -> Option<Matrix<OrderedFloat<f64>>> {
+    #[cfg(not(feature = "parallel"))]
+    return shortest_distances_sequential(imbalanced_nodes, shortest_distance, deadline);
+    #[cfg(feature = "parallel")]
+    return shortest_distances_parallel(imbalanced_nodes, shortest_distance, deadline);
+}
+
+/// Fills the distance matrix one row at a time.
+#[cfg(any(not(feature = "parallel"), test))]
+fn shortest_distances_sequential(
+    imbalanced_nodes: &ImbalancedNodeSet,
+    shortest_distance: impl Fn(usize, usize) -> f64,
+    deadline: Option<Instant>,
+) -> Option<Matrix<OrderedFloat<f64>>> {
+    let rows = imbalanced_nodes.negative.len();
+    let columns = imbalanced_nodes.positive.len();
+    let mut distances = Vec::with_capacity(rows * columns);
+    for &from in &imbalanced_nodes.negative {
+        if is_past(deadline) {
+            return None;
+        }
+        distances.extend(
+            imbalanced_nodes
+                .positive
+                .iter()
+                .map(|&to| OrderedFloat(shortest_distance(from, to))),
+        );
+    }
+    Some(Matrix::from_vec(rows, columns, distances).expect("one distance per pair of nodes"))
+}
+
+/// Fills the distance matrix with the rows computed in parallel, since each is independent.
+#[cfg(feature = "parallel")]
+fn shortest_distances_parallel(
+    imbalanced_nodes: &ImbalancedNodeSet,
+    shortest_distance: impl Fn(usize, usize) -> f64 + Sync,
+    deadline: Option<Instant>,
+) -> Option<Matrix<OrderedFloat<f64>>> {
+    use rayon::prelude::*;
+
+    let rows = imbalanced_nodes.negative.len();
+    let columns = imbalanced_nodes.positive.len();
+    let distances: Vec<Vec<_>> = imbalanced_nodes
+        .negative
+        .par_iter()
+        .map(|&from| {
+            if is_past(deadline) {
+                return None;
+            }
+            Some(
+                imbalanced_nodes
+                    .positive
+                    .iter()
+                    .map(|&to| OrderedFloat(shortest_distance(from, to)))
+                    .collect(),
+            )
+        })
+        .collect::<Option<_>>()?;
+    Some(
+        Matrix::from_vec(rows, columns, distances.concat())
+            .expect("one distance per pair of nodes"),
     )
 }
+
+/// Test that the parallel and sequential distance matrices agree.
+#[cfg(feature = "parallel")]
+#[test]
+fn test_parallel_distances_match_sequential() {
+    let imbalanced_nodes = ImbalancedNodeSet {
+        negative: (0..300).collect(),
+        positive: (300..600).collect(),
+    };
+    let shortest_distance = |from: usize, to: usize| ((from * 7919 + to * 104729) % 1000) as f64;
+    assert_eq!(
+        shortest_distances_parallel(&imbalanced_nodes, shortest_distance, None),
+        shortest_distances_sequential(&imbalanced_nodes, shortest_distance, None)
+    );
+}
+
+/// Test that unequal imbalance counts leave the costliest extra nodes unmatched.
+#[test]
+fn test_best_match_unequal_counts() {
+    let distances = [
+        [0.0, 0.0, 5.0, 1.0],
+        [0.0, 0.0, 2.0, 9.0],
+        [0.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 0.0],
+    ];
+    let pairs = |imbalanced_nodes: &ImbalancedNodeSet| {
+        best_match(imbalanced_nodes, |from, to| distances[from][to], None)
+            .unwrap()
+            .iter()
+            .map(|m| (m.from, m.to))
+            .collect::<Vec<_>>()
+    };
+    let more_positive = ImbalancedNodeSet {
+        negative: vec![1],
+        positive: vec![2, 3],
+    };
+    assert_eq!(pairs(&more_positive), [(1, 2)]);
+    let more_negative = ImbalancedNodeSet {
+        negative: vec![0, 1],
+        positive: vec![3],
+    };
+    assert_eq!(pairs(&more_negative), [(0, 3)]);
+}
+
+/// Test that a deadline passed before the distance matrix is built gives up on the matching.
+#[test]
+fn test_best_match_deadline() {
+    let imbalanced_nodes = ImbalancedNodeSet {
+        negative: vec![0, 1],
+        positive: vec![2, 3],
+    };
+    let distance = |from: usize, to: usize| (from + to) as f64;
+    assert!(best_match(&imbalanced_nodes, distance, Some(Instant::now())).is_none());
+    let future = Instant::now() + std::time::Duration::from_secs(60);
+    assert_eq!(
+        best_match(&imbalanced_nodes, distance, Some(future))
+            .unwrap()
+            .len(),
+        2
+    );
+}
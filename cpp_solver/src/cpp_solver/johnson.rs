@@ -0,0 +1,56 @@
+use super::Graph;
+use crate::Weight;
+
+/// Computes node potentials for Johnson's algorithm with Bellman-Ford.
+///
+/// The potentials are the shortest distances from a virtual node joined to every node by a
+/// zero-weight edge, so `w + potentials[u] - potentials[v]` is non-negative for every edge.
+///
+/// # Returns
+///
+/// The potential of each node, or `None` if the graph has a negative cycle.
+pub(super) fn potentials<W: Weight>(graph: &Graph<W>) -> Option<Vec<W>> {
+    let weight_matrix = graph.weight_matrix();
+    let edges: Vec<(usize, usize, W)> = weight_matrix
+        .indexed_iter()
+        .filter_map(|((from, to), &weight)| Some((from, to, weight?)))
+        .collect();
+    let mut potentials = vec![W::zero(); weight_matrix.nrows()];
+
+    for _ in 0..potentials.len() {
+        let mut relaxed = false;
+        for &(from, to, weight) in &edges {
+            if potentials[from] + weight < potentials[to] {
+                potentials[to] = potentials[from] + weight;
+                relaxed = true;
+            }
+        }
+        if !relaxed {
+            return Some(potentials);
+        }
+    }
+    // Still relaxing after every node was counted means a negative cycle
+    None
+}
+
+/// Test that potentials make every weight non-negative, and that negative cycles are found.
+#[test]
+fn test_potentials() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 2.0)
+        .add_edge(1, 2, -3.0)
+        .add_edge(2, 0, 4.0);
+    let graph = builder.build();
+    let shift = potentials(&graph).unwrap();
+    for ((from, to), &weight) in graph.weight_matrix().indexed_iter() {
+        if let Some(weight) = weight {
+            assert!(weight + shift[from] - shift[to] >= 0.0);
+        }
+    }
+
+    let mut builder = GraphBuilder::new();
+    builder.add_edge(0, 1, 1.0).add_edge(1, 0, -2.0);
+    assert!(potentials(&builder.build()).is_none());
+}
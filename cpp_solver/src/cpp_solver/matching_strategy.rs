@@ -0,0 +1,14 @@
+/// Selects how the solver pairs imbalanced nodes before duplicating the paths between them.
+///
+/// Only directed solves use it; undirected solves always pair odd-degree nodes with the
+/// Blossom algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchingStrategy {
+    /// Finds the pairing of minimum total distance with the Hungarian algorithm, in cubic time
+    /// in the number of imbalanced nodes.
+    #[default]
+    Hungarian,
+    /// Pairs each node with the nearest node not paired yet. Much faster with many imbalanced
+    /// nodes, but the route may cost more than the optimal one.
+    Greedy,
+}
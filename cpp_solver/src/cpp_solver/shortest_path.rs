@@ -0,0 +1,46 @@
+use super::{dijkstra::DijkstraRunner, floyd_warshall::FloydWarshallRunner};
+use crate::Weight;
+
+/// Selects how the solver computes the shortest distances used for balancing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShortestPathStrategy {
+    /// Computes all-pairs shortest distances up front. Supports negative weights.
+    ///
+    /// Skipped when the graph is already balanced, since no shortest paths are needed.
+    #[default]
+    FloydWarshall,
+    /// Runs Dijkstra only from each imbalanced node, keeping the distances between imbalanced
+    /// nodes instead of a full distance matrix. Requires non-negative weights.
+    ///
+    /// Shortest paths are then only available from imbalanced nodes.
+    Dijkstra,
+    /// Like `Dijkstra`, but first reweights the graph with Bellman-Ford potentials, so negative
+    /// weights are supported. Suited to large sparse graphs.
+    Johnson,
+}
+
+/// Shortest-path queries used while balancing the graph.
+///
+/// The Johnson strategy also answers through `Dijkstra`, with the runner built from potentials.
+pub(super) enum ShortestPaths<'a, W> {
+    FloydWarshall(&'a FloydWarshallRunner<W>),
+    Dijkstra(DijkstraRunner<W>),
+}
+
+impl<W: Weight> ShortestPaths<'_, W> {
+    /// Returns the shortest distance between two nodes, or `None` if `to` is unreachable.
+    pub(super) fn shortest_distance(&self, from: usize, to: usize) -> Option<W> {
+        match self {
+            ShortestPaths::FloydWarshall(runner) => runner.shortest_distances()[(from, to)],
+            ShortestPaths::Dijkstra(runner) => runner.shortest_distance(from, to),
+        }
+    }
+
+    /// Retrieves the shortest path between two nodes.
+    pub(super) fn shortest_path_between(&self, start: usize, end: usize) -> Vec<usize> {
+        match self {
+            ShortestPaths::FloydWarshall(runner) => runner.shortest_path_between(start, end),
+            ShortestPaths::Dijkstra(runner) => runner.shortest_path_between(start, end),
+        }
+    }
+}
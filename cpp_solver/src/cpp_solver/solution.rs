@@ -0,0 +1,57 @@
+use std::fmt;
+
+use super::Path;
+use crate::Weight;
+
+/// A solved route together with the figures describing it.
+#[derive(Clone)]
+pub struct Solution<W = f64> {
+    /// The route itself.
+    pub path: Path<W>,
+    /// The cost of the whole route, the same as `path.cost`.
+    pub total_cost: W,
+    /// The cost of walking every edge of the graph once.
+    pub required_cost: W,
+    /// The cost of walking edges again to balance the graph, the same as `path.balancing_cost`.
+    pub deadhead_cost: W,
+    /// The number of edges the route walks, counting repeated edges every time.
+    pub n_edges_traversed: usize,
+    /// The node the route starts at.
+    pub start: usize,
+    /// Whether the route ends at its start node, rather than being an open route.
+    pub is_circuit: bool,
+}
+
+impl<W: Weight> Solution<W> {
+    /// Gathers the figures of a solved route.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - A non-empty route returned by the solver.
+    pub(super) fn new(path: Path<W>) -> Self {
+        let start = path.path[0];
+        Self {
+            total_cost: path.cost,
+            required_cost: path.cost - path.balancing_cost,
+            deadhead_cost: path.balancing_cost,
+            n_edges_traversed: path.path.len() - 1,
+            start,
+            is_circuit: path.is_circuit(),
+            path,
+        }
+    }
+}
+
+impl<W: Weight> fmt::Debug for Solution<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Solution")
+            .field("path", &self.path)
+            .field("total_cost", &self.total_cost)
+            .field("required_cost", &self.required_cost)
+            .field("deadhead_cost", &self.deadhead_cost)
+            .field("n_edges_traversed", &self.n_edges_traversed)
+            .field("start", &self.start)
+            .field("is_circuit", &self.is_circuit)
+            .finish()
+    }
+}
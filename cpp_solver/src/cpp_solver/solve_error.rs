@@ -0,0 +1,74 @@
+use std::{error::Error, fmt};
+
+/// Represents the reasons the Chinese Postman Problem can't be solved on a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveError {
+    /// The graph has no nodes.
+    EmptyGraph,
+    /// Some node can't reach some other node. Holds the strongly connected components, as
+    /// returned by `Graph::strongly_connected_components`.
+    NotStronglyConnected(Vec<Vec<usize>>),
+    /// The graph contains a cycle of negative total weight.
+    NegativeCycle,
+    /// The graph has a negative weight, which the chosen shortest path strategy doesn't support.
+    NegativeWeight,
+    /// An undirected solve was requested, but some edge has no reverse edge of the same weight.
+    NotUndirected,
+    /// The requested start node doesn't exist or has no outgoing edge.
+    InvalidStartNode(usize),
+    /// Balancing finished but some node still has unequal in and out degrees. Holds the
+    /// `(from, to)` edges touching such nodes, for undirected solves the nodes of odd degree.
+    NotEulerianAfterBalancing { uncovered: Vec<(usize, usize)> },
+    /// Two nodes to pair while balancing are too far apart to measure in the weight type, or the
+    /// cost of the route doesn't fit in it.
+    DistanceOverflow,
+    /// The route walks an edge more often than `SolverConfig::max_traversals` allows.
+    ConstraintViolation {
+        from: usize,
+        to: usize,
+        traversals: usize,
+    },
+    /// The solve ran past `SolverConfig::timeout`.
+    TimedOut,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::EmptyGraph => write!(f, "the graph is empty"),
+            SolveError::NotStronglyConnected(components) => write!(
+                f,
+                "the graph is not strongly connected, it splits into {} components",
+                components.len()
+            ),
+            SolveError::NegativeCycle => write!(f, "the graph has a negative cycle"),
+            SolveError::NegativeWeight => write!(
+                f,
+                "the graph has a negative weight, which the shortest path strategy does not support"
+            ),
+            SolveError::NotUndirected => write!(f, "the graph is not undirected"),
+            SolveError::InvalidStartNode(node) => write!(f, "node {} can't start a route", node),
+            SolveError::NotEulerianAfterBalancing { uncovered } => write!(
+                f,
+                "the graph is not Eulerian after balancing, {} edges touch unbalanced nodes",
+                uncovered.len()
+            ),
+            SolveError::DistanceOverflow => write!(
+                f,
+                "a shortest distance between nodes to pair, or the route cost, overflows the weight type"
+            ),
+            SolveError::ConstraintViolation {
+                from,
+                to,
+                traversals,
+            } => write!(
+                f,
+                "the route walks the edge from {} to {} {} times, more than allowed",
+                from, to, traversals
+            ),
+            SolveError::TimedOut => write!(f, "the solve ran past its timeout"),
+        }
+    }
+}
+
+impl Error for SolveError {}
@@ -0,0 +1,30 @@
+use std::collections::VecDeque;
+
+use crate::graph::ImbalancedNodeSet;
+
+/// Hooks into the stages of a directed solve, e.g. to show or record how the route is built.
+///
+/// Every method does nothing by default, so an observer only implements the stages it cares
+/// about. `()` is the observer that ignores everything.
+pub trait SolveObserver<W = f64> {
+    /// Called with the imbalanced nodes before they are paired, if there are any.
+    fn on_imbalance(&mut self, _imbalanced_nodes: &ImbalancedNodeSet) {}
+
+    /// Called for each pair of imbalanced nodes the matching chose.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The node with more incoming than outgoing edges.
+    /// * `to` - The node with more outgoing than incoming edges.
+    /// * `cost` - The shortest distance from `from` to `to`.
+    fn on_match(&mut self, _from: usize, _to: usize, _cost: W) {}
+
+    /// Called for each shortest path walked a second time to balance the graph, as the nodes
+    /// from the start of the path to its end.
+    fn on_balancing_path(&mut self, _path: &[usize]) {}
+
+    /// Called with the finished route, before it is returned.
+    fn on_euler_path(&mut self, _path: &VecDeque<usize>) {}
+}
+
+impl<W> SolveObserver<W> for () {}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use super::{MatchingStrategy, ShortestPathStrategy};
+
+/// Options for solving the Chinese Postman Problem.
+///
+/// Start from `SolverConfig::default()` and override the options you need, so new options
+/// don't break existing code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SolverConfig {
+    /// The node the route starts and ends at, e.g. a depot. Defaults to node 0.
+    pub start: usize,
+    /// How shortest distances between nodes are computed.
+    pub strategy: ShortestPathStrategy,
+    /// How imbalanced nodes are paired in directed solves.
+    pub matching: MatchingStrategy,
+    /// Whether `solve` and its variants solve the undirected problem, like `solve_undirected`.
+    ///
+    /// Every street must be stored in both directions with the same weight, and is walked in
+    /// only one of them. Odd-degree nodes are paired with a minimum-weight perfect matching, and
+    /// the directed-only options `matching`, `open` and `max_traversals` are ignored. Defaults to
+    /// `false`.
+    pub undirected: bool,
+    /// Whether a directed route may end at another node than it starts at.
+    ///
+    /// Only semi-Eulerian graphs, with one node having one more outgoing than incoming edge and
+    /// one node the other way round, get an open route. It walks every edge exactly once from the
+    /// first of these nodes to the second, ignoring the start node. Other graphs are still solved
+    /// with a circuit. Defaults to `false`.
+    pub open: bool,
+    /// The most times a directed route may walk each edge, or `None` for no limit.
+    ///
+    /// Parallel edges between the same nodes share their limits, so two of them may be walked
+    /// twice as often in total. A route walking some edge more often is rejected with
+    /// `SolveError::ConstraintViolation`. Defaults to `None`.
+    pub max_traversals: Option<usize>,
+    /// Whether to return the lexicographically smallest route by node index, e.g. for stable
+    /// output to compare across runs.
+    ///
+    /// Each step walks to the smallest neighbour that still completes the route. Otherwise the
+    /// order edges were added in decides. Defaults to `false`.
+    pub canonical_output: bool,
+    /// How long a solve may take, or `None` for no limit, e.g. to bound the latency of a service.
+    ///
+    /// Floyd-Warshall checks the time before relaxing through each node, and balancing checks
+    /// it after finding the shortest paths, before each row of the matching's distance matrix,
+    /// before each stage of the Blossom algorithm and after the matching. The Kuhn-Munkres call
+    /// of the Hungarian matching can't be interrupted, so a solve may overrun the timeout by it
+    /// or by one of the other steps. A late solve fails with `SolveError::TimedOut`. Defaults to
+    /// `None`.
+    pub timeout: Option<Duration>,
+}
@@ -1,90 +1,252 @@
+mod csv_reader;
+mod dot;
 mod edge;
 mod graph_builder;
+mod graph_diff;
+mod graph_error;
+#[cfg(feature = "serde")]
+mod graph_serde;
 mod imbalanced_nodeset;
+mod parse_error;
+#[cfg(feature = "rand")]
+mod random;
 use edge::Edge;
 
+pub use csv_reader::CsvOptions;
 pub use graph_builder::GraphBuilder;
-pub(crate) use imbalanced_nodeset::ImbalancedNodeSet;
+pub use graph_diff::GraphDiff;
+pub use graph_error::GraphError;
+pub use imbalanced_nodeset::ImbalancedNodeSet;
+pub use parse_error::ParseError;
 
-use ndarray::{Array1, Array2, ArrayView1};
-use std::collections::HashMap;
+use crate::Weight;
+use ndarray::{Array1, Array2, ArrayView2};
+use std::{collections::BTreeMap, fmt};
 
-/// Represents a graph, with weight matrix, out degrees, edge count, and node labels.
-pub struct Graph {
-    weight_matrix: Array2<f64>,
+/// The `(neighbour, edge index, weight)` triples of each node of an undirected graph.
+pub(crate) type UndirectedEdgeSet<W> = Vec<Vec<(usize, usize, W)>>;
+
+/// Represents a graph, with weight matrix, out degrees, edges, and node labels.
+///
+/// The graph may have parallel edges with different weights. The weight matrix holds the
+/// cheapest weight between each pair of nodes, or `None` if there is no edge, while `edges`
+/// keeps every edge.
+#[derive(Clone)]
+pub struct Graph<W = f64> {
+    weight_matrix: Array2<Option<W>>,
     node_labels: Vec<String>,
-    edge_counts: HashMap<(usize, usize), usize>,
+    edges: Vec<Edge<W>>,
     out_degrees: Array1<usize>,
+    /// The `(longitude, latitude)` of each node, if known.
+    coordinates: Vec<Option<(f64, f64)>>,
 }
 
-impl Graph {
+impl<W: Weight> Graph<W> {
     /// Constructs a new Graph from a weight matrix.
-    pub fn new(weight_matrix: Array2<f64>, node_labels: Vec<String>) -> Self {
+    ///
+    /// # Panics
+    ///
+    /// If an entry is NaN, since it can't be compared with other weights, or infinite, since a
+    /// missing edge is `None`. Use `try_new` to get this as an error instead.
+    pub fn new(weight_matrix: Array2<Option<W>>, node_labels: Vec<String>) -> Self {
+        Self::try_new(weight_matrix, node_labels).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Constructs a new Graph from a weight matrix, unless an entry is NaN or infinite.
+    ///
+    /// # Returns
+    ///
+    /// The graph, or `GraphError::NanWeight` or `GraphError::InfiniteWeight` for the first such
+    /// entry.
+    pub fn try_new(
+        weight_matrix: Array2<Option<W>>,
+        node_labels: Vec<String>,
+    ) -> Result<Self, GraphError> {
+        for ((from, to), weight) in weight_matrix.indexed_iter() {
+            if let Some(weight) = *weight {
+                GraphError::check_weight(from, to, weight)?;
+            }
+        }
         let out_degrees = weight_matrix
             .rows()
             .into_iter()
-            .map(|row| row.iter().filter(|&&x| x != f64::INFINITY).count())
+            .map(|row| row.iter().filter(|x| x.is_some()).count())
             .collect();
-        let edge_counts = Self::compute_edge_counts(&weight_matrix);
-        Self {
+        let edges = Self::compute_edges(&weight_matrix);
+        Ok(Self {
+            coordinates: vec![None; weight_matrix.nrows()],
             weight_matrix,
             node_labels,
-            edge_counts,
+            edges,
             out_degrees,
-        }
+        })
     }
 
+    /// Constructs a new Graph from a weight matrix, with numeric labels if none are given.
+    ///
+    /// # Panics
+    ///
+    /// If an entry is NaN or infinite. Use `try_from_weight_matrix` to get this as an error
+    /// instead.
     pub fn from_weight_matrix(
-        weight_matrix: Array2<f64>,
+        weight_matrix: Array2<Option<W>>,
         node_labels: Option<Vec<String>>,
     ) -> Self {
-        let out_degrees = weight_matrix
-            .rows()
-            .into_iter()
-            .map(|row| row.iter().filter(|&&x| x != f64::INFINITY).count())
-            .collect();
-        let edge_counts = Self::compute_edge_counts(&weight_matrix);
+        Self::try_from_weight_matrix(weight_matrix, node_labels)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
+    /// Constructs a new Graph from a weight matrix like `from_weight_matrix`, unless an entry is
+    /// NaN or infinite.
+    ///
+    /// # Returns
+    ///
+    /// The graph, or the error of `try_new`.
+    pub fn try_from_weight_matrix(
+        weight_matrix: Array2<Option<W>>,
+        node_labels: Option<Vec<String>>,
+    ) -> Result<Self, GraphError> {
         // If no labels are provided, generate default numeric labels
         let labels = node_labels
             .unwrap_or_else(|| (0..weight_matrix.nrows()).map(|i| i.to_string()).collect());
 
-        Self {
-            weight_matrix,
-            node_labels: labels,
-            edge_counts,
-            out_degrees,
-        }
+        Self::try_new(weight_matrix, labels)
     }
 
-    /// Computes edge counts from a weight matrix.
-    fn compute_edge_counts(weight_matrix: &Array2<f64>) -> HashMap<(usize, usize), usize> {
-        let mut counts = HashMap::new();
-        for (i, row) in weight_matrix.rows().into_iter().enumerate() {
-            for (j, &weight) in row.iter().enumerate() {
-                if weight != f64::INFINITY {
-                    *counts.entry((i, j)).or_insert(0) += 1;
-                }
+    /// Constructs a new Graph from an adjacency list.
+    ///
+    /// `adjacency_list[from]` holds the `(to, weight)` pairs of the outgoing edges of `from`, so the
+    /// graph has one node per entry. Repeated entries between the same nodes are kept as parallel
+    /// edges, the same way `add_edge` treats them.
+    pub fn from_adjacency_list(
+        adjacency_list: Vec<Vec<(usize, W)>>,
+        node_labels: Option<Vec<String>>,
+    ) -> Self {
+        let mut graph = Self::without_edges(adjacency_list.len(), node_labels);
+        for (from, neighbours) in adjacency_list.into_iter().enumerate() {
+            for (to, weight) in neighbours {
+                graph.add_edge(from, to, weight);
             }
         }
-        counts
+        graph
+    }
+
+    /// Constructs a new Graph from a list of `(from, to, weight)` edges, like `GraphBuilder`.
+    ///
+    /// The graph has one node per index up to the largest one given. Repeated edges between the
+    /// same nodes are kept as parallel edges.
+    ///
+    /// # Returns
+    ///
+    /// The graph, or the error of `GraphBuilder::try_add_edge` for the first invalid edge, or
+    /// `GraphError::LabelCountMismatch` if the labels are not one per node.
+    pub fn from_edges(
+        edges: &[(usize, usize, W)],
+        node_labels: Option<Vec<String>>,
+    ) -> Result<Self, GraphError> {
+        let mut builder = GraphBuilder::default();
+        for &(from, to, weight) in edges {
+            builder.try_add_edge(from, to, weight)?;
+        }
+        let mut graph = builder.build();
+        graph.relabel(node_labels)?;
+        Ok(graph)
+    }
+
+    /// Constructs a Graph with the given number of nodes and no edges.
+    fn without_edges(n_nodes: usize, node_labels: Option<Vec<String>>) -> Self {
+        Self::from_weight_matrix(Array2::from_elem((n_nodes, n_nodes), None), node_labels)
+    }
+
+    /// Computes the edges from a weight matrix, one per present entry.
+    fn compute_edges(weight_matrix: &Array2<Option<W>>) -> Vec<Edge<W>> {
+        weight_matrix
+            .indexed_iter()
+            .filter_map(|((from, to), &weight)| {
+                Some(Edge {
+                    from,
+                    to,
+                    weight: weight?,
+                })
+            })
+            .collect()
     }
 
     /// Adds an edge to the graph with a weight.
-    pub fn add_edge(&mut self, from: usize, to: usize, weight: f64) {
-        self.weight_matrix[[from, to]] = weight;
+    ///
+    /// An existing edge between the same nodes is kept, so both become parallel edges.
+    ///
+    /// # Panics
+    ///
+    /// If either node doesn't exist, or the weight is NaN, since it can't be compared with other
+    /// weights. Use `try_add_edge` to get these as an error instead.
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: W) {
+        if let Err(error) = self.try_add_edge(from, to, weight) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Adds an edge to the graph with a weight, unless the edge is invalid.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the edge was added, or `GraphError::IndexOutOfRange` if either node doesn't
+    /// exist, or `GraphError::NanWeight` or `GraphError::InfiniteWeight` if the weight is not
+    /// finite.
+    pub fn try_add_edge(&mut self, from: usize, to: usize, weight: W) -> Result<(), GraphError> {
+        let n_nodes = self.node_count();
+        if let Some(index) = [from, to].into_iter().find(|&index| index >= n_nodes) {
+            return Err(GraphError::IndexOutOfRange { index, n_nodes });
+        }
+        GraphError::check_weight(from, to, weight)?;
+        self.keep_cheapest(from, to, weight);
         self.out_degrees[from] += 1;
-        *self.edge_counts.entry((from, to)).or_insert(0) += 1;
+        self.edges.push(Edge { from, to, weight });
+        Ok(())
+    }
+
+    /// Iterates over the `(from, to, weight)` of every edge, sorted by nodes and then weight.
+    fn sorted_edges(&self) -> impl Iterator<Item = (usize, usize, W)> {
+        let mut edges: Vec<_> = self
+            .edges
+            .iter()
+            .map(|&Edge { from, to, weight }| (from, to, weight))
+            .collect();
+        edges.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)).then(a.2.total_cmp(&b.2)));
+        edges.into_iter()
+    }
+
+    /// Removes every edge after the first `n_edges`, e.g. the edges added while balancing.
+    pub(crate) fn truncate_edges(&mut self, n_edges: usize) {
+        if n_edges >= self.edges.len() {
+            return;
+        }
+        for edge in self.edges.drain(n_edges..) {
+            self.out_degrees[edge.from] -= 1;
+        }
+        // A removed edge may have been the cheapest one, so rebuild the matrix from the rest
+        self.weight_matrix.fill(None);
+        for index in 0..self.edges.len() {
+            let Edge { from, to, weight } = self.edges[index];
+            self.keep_cheapest(from, to, weight);
+        }
+    }
+
+    /// Stores the weight in the weight matrix if it is the cheapest between the two nodes.
+    fn keep_cheapest(&mut self, from: usize, to: usize, weight: W) {
+        let cheapest = &mut self.weight_matrix[[from, to]];
+        if cheapest.is_none_or(|cheapest| weight < cheapest) {
+            *cheapest = Some(weight);
+        }
     }
 
     /// Returns the in-degrees of the nodes.
     pub fn in_degrees(&self) -> Array1<usize> {
-        self.weight_matrix
-            .t()
-            .rows()
-            .into_iter()
-            .map(|row| row.iter().filter(|&&x| x != f64::INFINITY).count())
-            .collect()
+        let mut in_degrees = Array1::zeros(self.weight_matrix.ncols());
+        for edge in &self.edges {
+            in_degrees[edge.to] += 1;
+        }
+        in_degrees
     }
 
     /// Returns the out-degrees of the nodes.
@@ -92,28 +254,164 @@ impl Graph {
         self.out_degrees.clone()
     }
 
+    /// Returns the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.weight_matrix.nrows()
+    }
+
+    /// Returns the number of edges in the graph, counting each parallel edge.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Returns the total weight of every edge, counting each parallel edge.
+    ///
+    /// Every route walks each edge at least once, so this is a lower bound on the cost of any
+    /// route. The cost of a solved route minus this bound is the deadheading overhead.
+    pub fn required_edge_cost(&self) -> W {
+        W::compensated_sum(self.edges.iter().map(|edge| edge.weight))
+    }
+
+    /// Returns the number of edges leaving the node.
+    ///
+    /// # Panics
+    ///
+    /// If the node is not in the graph.
+    pub fn out_degree(&self, node: usize) -> usize {
+        self.out_degrees[node]
+    }
+
+    /// Returns the number of edges entering the node.
+    ///
+    /// # Panics
+    ///
+    /// If the node is not in the graph.
+    pub fn in_degree(&self, node: usize) -> usize {
+        assert!(
+            node < self.node_count(),
+            "node {} is not in the graph",
+            node
+        );
+        self.edges.iter().filter(|edge| edge.to == node).count()
+    }
+
+    /// Returns the share of ordered pairs of distinct nodes joined by an edge, from 0 to 1.
+    ///
+    /// Parallel edges count once and self-loops not at all. A graph with fewer than two nodes
+    /// has a density of 0.
+    pub fn density(&self) -> f64 {
+        let n_nodes = self.node_count();
+        if n_nodes < 2 {
+            return 0.0;
+        }
+        let n_pairs = self
+            .weight_matrix
+            .indexed_iter()
+            .filter(|&((from, to), weight)| from != to && weight.is_some())
+            .count();
+        n_pairs as f64 / (n_nodes * (n_nodes - 1)) as f64
+    }
+
+    /// Counts the nodes of each degree, where the degree of a node is its in-degree plus its
+    /// out-degree.
+    ///
+    /// # Returns
+    ///
+    /// The `(degree, node count)` pairs of every degree some node has, in ascending order.
+    pub fn degree_distribution(&self) -> Vec<(usize, usize)> {
+        let mut counts = BTreeMap::new();
+        for (out_degree, in_degree) in self.out_degrees.iter().zip(self.in_degrees()) {
+            *counts.entry(out_degree + in_degree).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+
+    /// Iterates over the outgoing edges of a node as `(to, weight)`, in the order they were added.
+    ///
+    /// Each parallel edge is yielded on its own, with its own weight.
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = (usize, W)> + '_ {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.from == node)
+            .map(|edge| (edge.to, edge.weight))
+    }
+
     /// Retrieves the edge set in a sparse representation.
+    ///
+    /// Neighbours are listed in the order their edges were added, so the same input always
+    /// yields the same Euler circuit.
     pub fn edge_set(&self) -> Vec<Vec<usize>> {
+        self.weighted_edge_set()
+            .into_iter()
+            .map(|edges| edges.into_iter().map(|(to, _)| to).collect())
+            .collect()
+    }
+
+    /// Retrieves the outgoing edges of each node as `(to, weight)`, including parallel edges.
+    pub(crate) fn weighted_edge_set(&self) -> Vec<Vec<(usize, W)>> {
         let mut edge_set = vec![Vec::new(); self.weight_matrix.nrows()];
-        for (&(from, to), &count) in &self.edge_counts {
-            edge_set[from].extend(vec![to; count]);
+        for edge in &self.edges {
+            edge_set[edge.from].push((edge.to, edge.weight));
         }
         edge_set
     }
 
+    /// Exports the graph as a labeled adjacency list, e.g. for other graph tools.
+    ///
+    /// Unlike `edge_set`, neighbours are given by label along with the weight of the edge.
+    /// Parallel edges are listed once each, in the order they were added.
+    ///
+    /// # Returns
+    ///
+    /// The label of every node with its `(neighbour_label, weight)` pairs, in node order.
+    pub fn to_adjacency_list(&self) -> Vec<(String, Vec<(String, W)>)> {
+        self.node_labels
+            .iter()
+            .cloned()
+            .zip(self.weighted_edge_set())
+            .map(|(label, edges)| {
+                let neighbours = edges
+                    .into_iter()
+                    .map(|(to, weight)| (self.node_labels[to].clone(), weight))
+                    .collect();
+                (label, neighbours)
+            })
+            .collect()
+    }
+
+    /// Returns the out-degree minus the in-degree of each node.
+    fn degree_differences(&self) -> Vec<isize> {
+        self.out_degrees
+            .iter()
+            .zip(self.in_degrees().iter())
+            .map(|(&out_degree, &in_degree)| out_degree as isize - in_degree as isize)
+            .collect()
+    }
+
+    /// Reports how far each node is from being balanced.
+    ///
+    /// A positive difference means the node has more outgoing than incoming edges, so the route
+    /// must come back to it through extra edges. A missing street direction shows up as a
+    /// `+1`/`-1` pair.
+    ///
+    /// # Returns
+    ///
+    /// The label of every node with its out-degree minus in-degree, in node order.
+    pub fn imbalance_report(&self) -> Vec<(String, isize)> {
+        self.node_labels
+            .iter()
+            .cloned()
+            .zip(self.degree_differences())
+            .collect()
+    }
+
     /// Returns the set of imbalanced nodes in the graph.
     pub(crate) fn imbalanced_nodes(&self) -> ImbalancedNodeSet {
         let mut negative_difference_nodes = Vec::new();
         let mut positive_difference_nodes = Vec::new();
 
-        for (node, (row, col)) in self
-            .weight_matrix
-            .rows()
-            .into_iter()
-            .zip(self.weight_matrix.columns())
-            .enumerate()
-        {
-            match Graph::out_in_diff(&row, &col) {
+        for (node, difference) in self.degree_differences().into_iter().enumerate() {
+            match difference {
                 x if x > 0 => positive_difference_nodes.extend(vec![node; x as usize]),
                 x if x < 0 => negative_difference_nodes.extend(vec![node; (-x) as usize]),
                 _ => (),
@@ -125,24 +423,328 @@ impl Graph {
         }
     }
 
-    /// Calculates the out-in degree difference of a node.
-    fn out_in_diff(row: &ArrayView1<f64>, col: &ArrayView1<f64>) -> isize {
-        let out_degree = row.iter().filter(|&&x| x != f64::INFINITY).count();
-        let in_degree = col.iter().filter(|&&x| x != f64::INFINITY).count();
-        out_degree as isize - in_degree as isize
+    /// Checks if every node has as many incoming as outgoing edges.
+    pub fn is_balanced(&self) -> bool {
+        self.degree_differences()
+            .into_iter()
+            .all(|difference| difference == 0)
+    }
+
+    /// Checks if the graph has an Euler circuit, so the solver walks every edge exactly once.
+    ///
+    /// The graph must be balanced and strongly connected. Like the solver, this rejects nodes
+    /// without any edge.
+    pub fn is_eulerian(&self) -> bool {
+        self.is_balanced() && self.is_strongly_connected()
+    }
+
+    /// Checks if every node can reach every other node, using a search forwards and backwards from node 0.
+    pub(crate) fn is_strongly_connected(&self) -> bool {
+        let n_nodes = self.weight_matrix.nrows();
+        n_nodes == 0
+            || (Self::reaches_all(self.weight_matrix.view())
+                && Self::reaches_all(self.weight_matrix.t()))
+    }
+
+    /// Checks if every node is reachable from node 0 in the given weight matrix.
+    fn reaches_all(weight_matrix: ArrayView2<Option<W>>) -> bool {
+        let mut visited = vec![false; weight_matrix.nrows()];
+        let mut stack = vec![0];
+        visited[0] = true;
+        while let Some(node) = stack.pop() {
+            for (next, weight) in weight_matrix.row(node).indexed_iter() {
+                if weight.is_some() && !visited[next] {
+                    visited[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        visited.into_iter().all(|x| x)
+    }
+
+    /// Splits the graph into its strongly connected components, using Tarjan's algorithm.
+    ///
+    /// Nodes in different components can't reach each other both ways, so a graph with more
+    /// than one component has no postman route.
+    ///
+    /// # Returns
+    ///
+    /// The nodes of each component in ascending order, with the components ordered by their
+    /// smallest node.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let edge_set = self.edge_set();
+        let n_nodes = edge_set.len();
+        let mut index = vec![None; n_nodes];
+        let mut low_link = vec![0; n_nodes];
+        let mut on_stack = vec![false; n_nodes];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..n_nodes {
+            if index[root].is_some() {
+                continue;
+            }
+            // Each frame holds a node and the position of its next neighbour to visit
+            let mut frames = vec![(root, 0)];
+            index[root] = Some(next_index);
+            low_link[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, position)) = frames.last_mut() {
+                let node = *node;
+                if let Some(&next) = edge_set[node].get(*position) {
+                    *position += 1;
+                    match index[next] {
+                        None => {
+                            frames.push((next, 0));
+                            index[next] = Some(next_index);
+                            low_link[next] = next_index;
+                            next_index += 1;
+                            stack.push(next);
+                            on_stack[next] = true;
+                        }
+                        Some(next_index) if on_stack[next] => {
+                            low_link[node] = low_link[node].min(next_index);
+                        }
+                        Some(_) => (),
+                    }
+                    continue;
+                }
+
+                frames.pop();
+                if let Some(&(parent, _)) = frames.last() {
+                    low_link[parent] = low_link[parent].min(low_link[node]);
+                }
+                if index[node] == Some(low_link[node]) {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+        components.sort_unstable();
+        components
+    }
+
+    /// Finds the bridges of the graph: the edges without which their start can't reach their
+    /// end any more, e.g. the only way into a dead-end street.
+    ///
+    /// In a strongly connected graph these are exactly the edges whose removal leaves no postman
+    /// route, and they often force long detours. A parallel edge or self-loop is never a bridge.
+    /// Each edge is checked with a search from its start, so this takes `O(E * (V + E))` time.
+    ///
+    /// # Returns
+    ///
+    /// The `(from, to)` bridges, ordered by `from` and then `to`.
+    pub fn bridges(&self) -> Vec<(usize, usize)> {
+        let edge_set = self.edge_set();
+        let mut bridges = Vec::new();
+        for (from, neighbours) in edge_set.iter().enumerate() {
+            let mut candidates: Vec<usize> = neighbours
+                .iter()
+                .copied()
+                .filter(|&to| to != from && neighbours.iter().filter(|&&x| x == to).count() == 1)
+                .collect();
+            candidates.sort_unstable();
+            bridges.extend(
+                candidates
+                    .into_iter()
+                    .filter(|&to| !Self::reaches_without(&edge_set, from, to))
+                    .map(|to| (from, to)),
+            );
+        }
+        bridges
+    }
+
+    /// Checks if `to` is reachable from `from` without walking the edge between them.
+    fn reaches_without(edge_set: &[Vec<usize>], from: usize, to: usize) -> bool {
+        let mut visited = vec![false; edge_set.len()];
+        let mut stack = vec![from];
+        visited[from] = true;
+        while let Some(node) = stack.pop() {
+            for &next in &edge_set[node] {
+                if node == from && next == to {
+                    continue; // Skip the edge itself
+                }
+                if next == to {
+                    return true;
+                }
+                if !visited[next] {
+                    visited[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    /// Checks if any edge has a negative weight.
+    pub(crate) fn has_negative_weights(&self) -> bool {
+        self.weight_matrix.iter().flatten().any(|&x| x < W::zero())
+    }
+
+    /// Returns the nodes with an odd degree when the graph is read as undirected.
+    pub(crate) fn odd_degree_nodes(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.weight_matrix.nrows()];
+        for edge in &self.edges {
+            if edge.from != edge.to {
+                degrees[edge.from] += 1;
+            }
+        }
+        (0..degrees.len())
+            .filter(|&node| degrees[node] % 2 == 1)
+            .collect()
+    }
+
+    /// Returns the `(from, to)` edges touching a node with unequal in- and out-degrees.
+    pub(crate) fn unbalanced_edges(&self) -> Vec<(usize, usize)> {
+        let differences = self.degree_differences();
+        self.edges_touching(|node| differences[node] != 0)
+    }
+
+    /// Returns the `(from, to)` edges touching a node with an odd undirected degree.
+    pub(crate) fn odd_degree_edges(&self) -> Vec<(usize, usize)> {
+        let odd_nodes = self.odd_degree_nodes();
+        self.edges_touching(|node| odd_nodes.binary_search(&node).is_ok())
+    }
+
+    /// Returns the `(from, to)` edges with either end matching the predicate, in the order they
+    /// were added.
+    fn edges_touching(&self, touches: impl Fn(usize) -> bool) -> Vec<(usize, usize)> {
+        self.edges
+            .iter()
+            .filter(|edge| touches(edge.from) || touches(edge.to))
+            .map(|edge| (edge.from, edge.to))
+            .collect()
+    }
+
+    /// Checks if every edge has a reverse edge with the same weight, i.e. the graph is undirected.
+    ///
+    /// Parallel edges must be paired one to one, so the sorted edges have to match the sorted
+    /// reversed edges exactly.
+    pub(crate) fn is_symmetric(&self) -> bool {
+        let sorted = |mut edges: Vec<(usize, usize, W)>| {
+            edges.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)).then(a.2.total_cmp(&b.2)));
+            edges
+        };
+        let forward = self
+            .edges
+            .iter()
+            .map(|edge| (edge.from, edge.to, edge.weight));
+        let backward = self
+            .edges
+            .iter()
+            .map(|edge| (edge.to, edge.from, edge.weight));
+        sorted(forward.collect()) == sorted(backward.collect())
+    }
+
+    /// Retrieves the undirected edge set of a symmetric graph, where each edge is listed at both
+    /// ends with a shared index.
+    ///
+    /// Each pair of opposite directed edges forms one undirected edge, so only the direction
+    /// going from the lower to the higher index is read. Self-loops are kept as they are.
+    ///
+    /// # Returns
+    ///
+    /// The `(neighbour, edge index, weight)` triples of each node, and the number of undirected edges.
+    pub(crate) fn undirected_edge_set(&self) -> (UndirectedEdgeSet<W>, usize) {
+        let mut edge_set = vec![Vec::new(); self.weight_matrix.nrows()];
+        let mut n_edges = 0;
+        for edge in self.edges.iter().filter(|edge| edge.from <= edge.to) {
+            edge_set[edge.from].push((edge.to, n_edges, edge.weight));
+            if edge.from != edge.to {
+                edge_set[edge.to].push((edge.from, n_edges, edge.weight));
+            }
+            n_edges += 1;
+        }
+        (edge_set, n_edges)
+    }
+
+    /// Returns a new graph with every edge reversed, keeping the same nodes and labels.
+    ///
+    /// Parallel edges and their weights are kept, so the weight matrix is the transpose of
+    /// this one.
+    pub fn transpose(&self) -> Graph<W> {
+        let mut transposed =
+            Self::without_edges(self.weight_matrix.nrows(), Some(self.node_labels.clone()));
+        for edge in &self.edges {
+            transposed.add_edge(edge.to, edge.from, edge.weight);
+        }
+        transposed.coordinates = self.coordinates.clone();
+        transposed
+    }
+
+    /// Extracts the subgraph induced by the given nodes.
+    ///
+    /// `nodes[i]` becomes node `i` of the subgraph and keeps its label. Edges with an end outside
+    /// the given nodes are dropped, while parallel edges inside are kept.
+    ///
+    /// # Panics
+    ///
+    /// If a node is not in the graph.
+    pub fn subgraph(&self, nodes: &[usize]) -> Graph<W> {
+        let mut new_indices = vec![None; self.node_count()];
+        for (new_index, &node) in nodes.iter().enumerate() {
+            new_indices[node] = Some(new_index);
+        }
+        let node_labels = nodes
+            .iter()
+            .map(|&node| self.node_labels[node].clone())
+            .collect();
+
+        let mut subgraph = Self::without_edges(nodes.len(), Some(node_labels));
+        subgraph.coordinates = nodes.iter().map(|&node| self.coordinates[node]).collect();
+        for edge in &self.edges {
+            if let (Some(from), Some(to)) = (new_indices[edge.from], new_indices[edge.to]) {
+                subgraph.add_edge(from, to, edge.weight);
+            }
+        }
+        subgraph
     }
 
     /// Relabels the nodes in the graph with the given labels.
-    pub fn relabel(&mut self, node_labels: Option<Vec<String>>) {
-        self.node_labels = node_labels.unwrap_or_else(|| {
-            (0..self.weight_matrix.nrows())
-                .map(|i| i.to_string())
-                .collect()
-        });
+    ///
+    /// `None` restores the numeric labels.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the graph was relabeled, or `GraphError::LabelCountMismatch` if there is not
+    /// exactly one label per node, in which case the labels are left unchanged.
+    pub fn relabel(&mut self, node_labels: Option<Vec<String>>) -> Result<(), GraphError> {
+        let n_nodes = self.weight_matrix.nrows();
+        let node_labels =
+            node_labels.unwrap_or_else(|| (0..n_nodes).map(|i| i.to_string()).collect());
+        if node_labels.len() != n_nodes {
+            return Err(GraphError::LabelCountMismatch {
+                expected: n_nodes,
+                found: node_labels.len(),
+            });
+        }
+        self.node_labels = node_labels;
+        Ok(())
+    }
+
+    /// Compares this graph with another one, matching nodes by label.
+    ///
+    /// # Returns
+    ///
+    /// The edges added, removed, and reweighted when going from this graph to `other`.
+    pub fn diff(&self, other: &Graph<W>) -> GraphDiff<W> {
+        GraphDiff::new(self, other)
     }
 
     /// Returns the weight matrix (for debugging or advanced usage).
-    pub fn weight_matrix(&self) -> &Array2<f64> {
+    pub fn weight_matrix(&self) -> &Array2<Option<W>> {
         &self.weight_matrix
     }
 
@@ -150,4 +752,393 @@ impl Graph {
     pub fn node_labels(&self) -> &[String] {
         &self.node_labels
     }
+
+    /// Returns the `(longitude, latitude)` of a node, if it was given with
+    /// `GraphBuilder::add_node_coords`.
+    pub fn coordinates(&self, node: usize) -> Option<(f64, f64)> {
+        self.coordinates.get(node).copied().flatten()
+    }
+
+    /// Looks up the index of the node with the given label.
+    ///
+    /// # Returns
+    ///
+    /// The index used in the weight matrix and in `Path.path`, or `None` if no node has the label.
+    pub fn index_of_label(&self, label: &str) -> Option<usize> {
+        self.node_labels.iter().position(|x| x == label)
+    }
+
+    /// Looks up the label of the node at the given index.
+    ///
+    /// # Returns
+    ///
+    /// The label, or `None` if the index is not in the graph.
+    pub fn label_of(&self, index: usize) -> Option<&str> {
+        self.node_labels.get(index).map(String::as_str)
+    }
+}
+
+impl Graph {
+    /// Multiplies the weight of every edge by a factor, e.g. to switch from meters to kilometers.
+    ///
+    /// Missing edges stay missing, so the connectivity of the graph doesn't change.
+    ///
+    /// # Panics
+    ///
+    /// If a scaled weight is NaN or infinite, e.g. for a NaN factor or one that overflows a
+    /// weight. Use `try_scale_weights` to get this as an error instead.
+    pub fn scale_weights(&mut self, factor: f64) {
+        if let Err(error) = self.try_scale_weights(factor) {
+            panic!("{}", error);
+        }
+    }
+
+    /// Multiplies the weight of every edge by a factor like `scale_weights`, unless a scaled
+    /// weight is not finite.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())`, or `GraphError::NanWeight` or `GraphError::InfiniteWeight` for the first edge
+    /// whose scaled weight is not finite, in which case no weight is changed.
+    pub fn try_scale_weights(&mut self, factor: f64) -> Result<(), GraphError> {
+        for edge in &self.edges {
+            GraphError::check_weight(edge.from, edge.to, edge.weight * factor)?;
+        }
+        self.weight_matrix
+            .mapv_inplace(|weight| weight.map(|weight| weight * factor));
+        for edge in &mut self.edges {
+            edge.weight *= factor;
+        }
+        Ok(())
+    }
+
+    /// Divides the weight of every edge by the largest absolute weight, so non-negative weights
+    /// fall in `[0, 1]`.
+    ///
+    /// A graph without edges, or whose weights are all zero, is left unchanged.
+    pub fn normalize_weights(&mut self) {
+        let largest = self
+            .edges
+            .iter()
+            .map(|edge| edge.weight.abs())
+            .fold(0.0, f64::max);
+        if largest > 0.0 {
+            self.scale_weights(largest.recip());
+        }
+    }
+}
+
+/// Converts a weight matrix where missing edges are infinite, with numeric labels.
+impl TryFrom<Array2<f64>> for Graph {
+    type Error = GraphError;
+
+    fn try_from(weight_matrix: Array2<f64>) -> Result<Self, Self::Error> {
+        let (rows, cols) = weight_matrix.dim();
+        if rows != cols {
+            return Err(GraphError::NotSquare { rows, cols });
+        }
+        // NaN entries are kept, so they are rejected rather than read as missing edges
+        Self::try_from_weight_matrix(
+            weight_matrix.mapv(|weight| Some(weight).filter(|weight| !weight.is_infinite())),
+            None,
+        )
+    }
+}
+
+/// Compares graphs by their labels and their edges, parallel ones included, in any order.
+///
+/// Weights are compared with `total_cmp`, so a NaN weight equals itself and a graph always equals
+/// its clone. Coordinates are not compared.
+impl<W: Weight> PartialEq for Graph<W> {
+    fn eq(&self, other: &Self) -> bool {
+        self.node_labels == other.node_labels
+            && self.edges.len() == other.edges.len()
+            && self
+                .sorted_edges()
+                .zip(other.sorted_edges())
+                .all(|(a, b)| (a.0, a.1) == (b.0, b.1) && a.2.total_cmp(&b.2).is_eq())
+    }
+}
+
+/// Summarizes the graph by its size and labels, instead of dumping the weight matrix.
+impl<W: Weight> fmt::Debug for Graph<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Graph")
+            .field("node_count", &self.node_count())
+            .field("edge_count", &self.edge_count())
+            .field("node_labels", &self.node_labels)
+            .finish()
+    }
+}
+
+/// Test that a NaN or infinite entry in a weight matrix is rejected by every constructor.
+#[test]
+fn test_new_nan_weight() {
+    let weight_matrix = ndarray::arr2(&[[None, Some(1.0)], [Some(f64::NAN), None]]);
+    let labels = vec!["a".to_string(), "b".to_string()];
+    let expected = Some(GraphError::NanWeight { from: 1, to: 0 });
+    assert_eq!(
+        Graph::try_new(weight_matrix.clone(), labels).err(),
+        expected
+    );
+    assert_eq!(
+        Graph::try_from_weight_matrix(weight_matrix, None).err(),
+        expected
+    );
+    assert_eq!(
+        Graph::try_from_weight_matrix(ndarray::arr2(&[[None, Some(f64::INFINITY)]]), None).err(),
+        Some(GraphError::InfiniteWeight { from: 0, to: 1 })
+    );
+    let graph = Graph::try_from_weight_matrix(
+        ndarray::arr2(&[[None, Some(-1.0)], [Some(1.0), None]]),
+        None,
+    )
+    .unwrap();
+    assert_eq!(graph.edge_count(), 2);
+}
+
+/// Test that `Graph::new` panics with a clear message for a NaN entry.
+#[test]
+#[should_panic(expected = "the edge from 0 to 0 has a NaN weight")]
+fn test_new_nan_weight_panics() {
+    Graph::new(ndarray::arr2(&[[Some(f64::NAN)]]), vec!["a".to_string()]);
+}
+
+/// Test that a graph equals its clone, but not after adding an edge.
+#[test]
+fn test_graph_eq() {
+    let mut graph = GraphBuilder::from(vec![(0, 1, 1.0), (1, 0, 2.0)]).build();
+    let clone = graph.clone();
+    assert_eq!(graph, clone);
+    assert_eq!(graph.transpose().transpose(), clone);
+
+    graph.add_edge(0, 1, 3.0);
+    assert_ne!(graph, clone, "a parallel edge changes the graph");
+    let mut relabeled = clone.clone();
+    relabeled
+        .relabel(Some(vec!["A".to_string(), "B".to_string()]))
+        .unwrap();
+    assert_ne!(relabeled, clone);
+}
+
+/// Test that a graph with a NaN weight still equals its clone.
+#[test]
+fn test_graph_eq_nan_weight() {
+    // No public method stores a NaN weight, so it is written into the fields directly to check
+    // that `total_cmp` keeps equality reflexive even then.
+    let mut graph = GraphBuilder::from(vec![(0, 1, 1.0), (1, 0, 2.0)]).build();
+    graph.edges[0].weight = f64::NAN;
+    graph.weight_matrix[[0, 1]] = Some(f64::NAN);
+    assert_eq!(graph, graph.clone());
+}
+
+/// Test that parallel edges are compared by all their weights, not just the cheapest one.
+#[test]
+fn test_graph_eq_parallel_edges() {
+    let graph = GraphBuilder::from(vec![(0, 1, 3.0), (0, 1, 5.0), (1, 0, 1.0)]).build();
+    let other = GraphBuilder::from(vec![(0, 1, 3.0), (0, 1, 3.0), (1, 0, 1.0)]).build();
+    assert_eq!(graph.weight_matrix, other.weight_matrix);
+    assert_ne!(graph, other);
+    let reordered = GraphBuilder::from(vec![(1, 0, 1.0), (0, 1, 5.0), (0, 1, 3.0)]).build();
+    assert_eq!(graph, reordered);
+}
+
+/// Test that adding an edge to a missing node is reported instead of indexing out of bounds.
+#[test]
+fn test_graph_add_edge_out_of_range() {
+    let mut graph = GraphBuilder::from(vec![(0, 1, 1.0), (1, 0, 1.0)]).build();
+    assert_eq!(
+        graph.try_add_edge(0, 2, 1.0),
+        Err(GraphError::IndexOutOfRange {
+            index: 2,
+            n_nodes: 2
+        })
+    );
+    assert_eq!(
+        graph.try_add_edge(1, 0, f64::NAN),
+        Err(GraphError::NanWeight { from: 1, to: 0 })
+    );
+    assert_eq!(
+        graph.try_add_edge(1, 0, f64::INFINITY),
+        Err(GraphError::InfiniteWeight { from: 1, to: 0 })
+    );
+    assert_eq!(graph.try_add_edge(1, 1, 2.0), Ok(()));
+    assert_eq!(graph.edge_count(), 3);
+}
+
+/// Test that `Graph::add_edge` panics with a clear message for a missing node.
+#[test]
+#[should_panic(expected = "node 5 is out of range for a graph with 2 nodes")]
+fn test_graph_add_edge_out_of_range_panics() {
+    let mut graph = GraphBuilder::from(vec![(0, 1, 1.0), (1, 0, 1.0)]).build();
+    graph.add_edge(5, 0, 1.0);
+}
+
+/// Test that a square weight matrix converts to a graph, with infinite entries as missing edges.
+#[test]
+fn test_try_from_weight_matrix() {
+    use ndarray::arr2;
+    let graph = Graph::try_from(arr2(&[[f64::INFINITY, 1.0], [2.0, f64::INFINITY]])).unwrap();
+    let expected = GraphBuilder::from(vec![(0, 1, 1.0), (1, 0, 2.0)]).build();
+    assert_eq!(graph.weight_matrix, expected.weight_matrix);
+    assert_eq!(graph.node_labels, expected.node_labels);
+    assert_eq!(graph.edges, expected.edges);
+
+    let rectangular = arr2(&[[0.0, 1.0, 2.0], [1.0, 0.0, 3.0]]);
+    assert_eq!(
+        Graph::try_from(rectangular).err(),
+        Some(GraphError::NotSquare { rows: 2, cols: 3 })
+    );
+    assert_eq!(
+        Graph::try_from(arr2(&[[f64::NAN]])).err(),
+        Some(GraphError::NanWeight { from: 0, to: 0 })
+    );
+}
+
+/// Test that relabeling requires one label per node.
+#[test]
+fn test_relabel() {
+    let mut graph = GraphBuilder::from(vec![(0, 1, 1.0), (1, 0, 1.0)]).build();
+    assert_eq!(
+        graph.relabel(Some(vec!["A".to_string()])),
+        Err(GraphError::LabelCountMismatch {
+            expected: 2,
+            found: 1
+        })
+    );
+    assert_eq!(graph.node_labels, ["0", "1"]);
+    graph
+        .relabel(Some(vec!["A".to_string(), "B".to_string()]))
+        .unwrap();
+    assert_eq!(graph.node_labels, ["A", "B"]);
+    graph.relabel(None).unwrap();
+    assert_eq!(graph.node_labels, ["0", "1"]);
+}
+
+/// Test that scaling and normalizing change every weight but no edge.
+#[test]
+fn test_scale_weights() {
+    let mut graph = GraphBuilder::from(vec![(0, 1, 2.0), (1, 0, 4.0), (1, 0, 8.0)]).build();
+    graph.scale_weights(0.5);
+    assert_eq!(graph.weight_matrix[[0, 1]], Some(1.0));
+    assert_eq!(graph.weight_matrix[[1, 0]], Some(2.0));
+    assert_eq!(graph.weight_matrix[[0, 0]], None);
+    assert_eq!(graph.required_edge_cost(), 7.0);
+    graph.normalize_weights();
+    assert_eq!(graph.weight_matrix[[0, 1]], Some(0.25));
+    assert_eq!(graph.required_edge_cost(), 1.75);
+    assert_eq!(graph.edge_count(), 3);
+
+    let mut empty = GraphBuilder::new().build();
+    empty.normalize_weights();
+    assert_eq!(empty.edge_count(), 0);
+
+    // A zero factor keeps every edge, at no cost
+    let mut zero = GraphBuilder::from(vec![(0, 1, 2.0), (1, 0, -4.0)]).build();
+    zero.scale_weights(0.0);
+    assert_eq!(zero.weight_matrix[[0, 1]], Some(0.0));
+    assert_eq!(zero.weight_matrix[[1, 0]], Some(0.0));
+    assert_eq!(zero.edge_count(), 2);
+}
+
+/// Test that scaling is rejected, leaving every weight unchanged, when a weight would not be
+/// finite.
+#[test]
+fn test_try_scale_weights() {
+    let mut graph = GraphBuilder::from(vec![(0, 1, 1.0), (1, 0, 1e308)]).build();
+    let original = graph.clone();
+    assert_eq!(
+        graph.try_scale_weights(10.0),
+        Err(GraphError::InfiniteWeight { from: 1, to: 0 })
+    );
+    assert_eq!(
+        graph.try_scale_weights(f64::NAN),
+        Err(GraphError::NanWeight { from: 0, to: 1 })
+    );
+    assert_eq!(graph, original);
+    assert_eq!(graph.weight_matrix, original.weight_matrix);
+}
+
+/// Test that `scale_weights` panics like `add_edge` when a weight would become infinite.
+#[test]
+#[should_panic(expected = "the edge from 0 to 1 has an infinite weight")]
+fn test_scale_weights_overflow_panics() {
+    let mut graph = GraphBuilder::from(vec![(0, 1, 1.0), (1, 0, 1.0)]).build();
+    graph.scale_weights(f64::INFINITY);
+}
+
+/// Test that a graph built from an edge list equals the one the builder yields.
+#[test]
+fn test_graph_from_edges() {
+    let edges = [(0, 1, 1.0), (1, 2, 2.0), (2, 0, 3.0), (2, 0, 4.0)];
+    let mut builder = GraphBuilder::new();
+    builder.add_edges(edges);
+    assert_eq!(Graph::from_edges(&edges, None), Ok(builder.build()));
+
+    let labels = ["A", "B", "C"].map(String::from).to_vec();
+    let graph = Graph::from_edges(&edges, Some(labels)).unwrap();
+    assert_eq!(graph.index_of_label("C"), Some(2));
+    assert_eq!(
+        Graph::from_edges(&edges, Some(vec!["A".to_string()])).err(),
+        Some(GraphError::LabelCountMismatch {
+            expected: 3,
+            found: 1
+        })
+    );
+    assert_eq!(
+        Graph::from_edges(&[(0, 1, 1.0), (1, 0, f64::INFINITY)], None).err(),
+        Some(GraphError::InfiniteWeight { from: 1, to: 0 })
+    );
+}
+
+/// Test that the only way into a dead-end street is a bridge, but edges on a loop are not.
+#[test]
+fn test_bridges() {
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_undirected_edge(0, 1, 1.0)
+        .add_undirected_edge(1, 2, 1.0)
+        .add_undirected_edge(2, 0, 1.0)
+        .add_undirected_edge(2, 3, 1.0)
+        .add_edge(3, 3, 1.0);
+    let graph = builder.build();
+    assert_eq!(graph.bridges(), [(2, 3), (3, 2)]);
+
+    // Every edge of a one-way loop is needed, unless it is doubled.
+    let mut graph = GraphBuilder::from(vec![(0, 1, 1.0), (1, 2, 1.0), (2, 0, 1.0)]).build();
+    assert_eq!(graph.bridges(), [(0, 1), (1, 2), (2, 0)]);
+    graph.add_edge(0, 1, 2.0);
+    assert_eq!(graph.bridges(), [(1, 2), (2, 0)]);
+}
+
+/// Test the density and degree distribution of the standard graph.
+#[test]
+fn test_density_and_degree_distribution() {
+    let mut builder = GraphBuilder::new();
+    builder.add_labeled_edges(vec![
+        ("a", "c", 20.0),
+        ("a", "b", 10.0),
+        ("b", "e", 10.0),
+        ("b", "d", 50.0),
+        ("c", "e", 33.0),
+        ("c", "d", 20.0),
+        ("d", "e", 5.0),
+        ("d", "f", 12.0),
+        ("e", "a", 12.0),
+        ("e", "f", 1.0),
+        ("f", "c", 22.0),
+    ]);
+    let mut graph = builder.build();
+    assert_eq!(graph.density(), 11.0 / 30.0);
+    assert_eq!(graph.degree_distribution(), [(3, 3), (4, 2), (5, 1)]);
+
+    // Parallel edges and self-loops don't join more pairs, but add to the degrees
+    graph.add_edge(0, 1, 1.0);
+    graph.add_edge(0, 0, 1.0);
+    assert_eq!(graph.density(), 11.0 / 30.0);
+    assert_eq!(
+        graph.degree_distribution(),
+        [(3, 2), (4, 1), (5, 2), (6, 1)]
+    );
+    assert_eq!(GraphBuilder::new().build().density(), 0.0);
 }
@@ -0,0 +1,195 @@
+use super::{GraphBuilder, ParseError};
+use std::io::BufRead;
+
+/// Options for reading a `from,to,weight` edge list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    /// The character separating the fields of a row.
+    pub delimiter: char,
+    /// Whether the first row is a header to skip.
+    pub has_header: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            has_header: false,
+        }
+    }
+}
+
+impl GraphBuilder {
+    /// Reads a comma separated `from,to,weight` edge list without a header.
+    ///
+    /// See `from_csv_reader_with` for the details.
+    pub fn from_csv_reader<R: BufRead>(reader: R) -> Result<GraphBuilder, ParseError> {
+        Self::from_csv_reader_with(reader, CsvOptions::default())
+    }
+
+    /// Reads a `from,to,weight` edge list, adding each row with `add_labeled_edge`.
+    ///
+    /// Fields are trimmed, and blank lines are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the rows.
+    /// * `options` - The delimiter, and whether to skip a header row.
+    ///
+    /// # Returns
+    ///
+    /// The builder holding every edge, or the first malformed row as a `ParseError`.
+    pub fn from_csv_reader_with<R: BufRead>(
+        reader: R,
+        options: CsvOptions,
+    ) -> Result<GraphBuilder, ParseError> {
+        let mut builder = GraphBuilder::new();
+        let skip = usize::from(options.has_header);
+        for (index, row) in reader.lines().enumerate().skip(skip) {
+            let line = index + 1;
+            let row = row.map_err(|source| ParseError::Io { line, source })?;
+            if row.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = row.split(options.delimiter).map(str::trim).collect();
+            let [from, to, weight] = fields[..] else {
+                return Err(ParseError::WrongFieldCount {
+                    line,
+                    found: fields.len(),
+                });
+            };
+            if from.is_empty() || to.is_empty() {
+                return Err(ParseError::EmptyLabel { line });
+            }
+            let weight = weight
+                .parse()
+                .ok()
+                .filter(|weight: &f64| weight.is_finite())
+                .ok_or_else(|| ParseError::InvalidWeight {
+                    line,
+                    weight: weight.to_string(),
+                })?;
+            builder.add_labeled_edge(from, to, weight);
+        }
+        Ok(builder)
+    }
+
+    /// Reads an edge list in a custom text format, one edge per line.
+    ///
+    /// The parser turns each line into a `(from_label, to_label, weight)` edge, which is added
+    /// with `add_labeled_edge`. Use `from_csv_reader` for comma separated rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the lines.
+    /// * `parse` - Parses a line, without its line ending, or returns `None` to skip it, e.g.
+    ///   for comments.
+    ///
+    /// # Returns
+    ///
+    /// The builder holding every edge, or the first unreadable line or weight that is not finite
+    /// as a `ParseError`.
+    pub fn read_edges<R, F>(reader: R, mut parse: F) -> Result<GraphBuilder, ParseError>
+    where
+        R: BufRead,
+        F: FnMut(&str) -> Option<(String, String, f64)>,
+    {
+        let mut builder = GraphBuilder::new();
+        for (index, row) in reader.lines().enumerate() {
+            let line = index + 1;
+            let row = row.map_err(|source| ParseError::Io { line, source })?;
+            let Some((from, to, weight)) = parse(&row) else {
+                continue;
+            };
+            if !weight.is_finite() {
+                return Err(ParseError::InvalidWeight {
+                    line,
+                    weight: weight.to_string(),
+                });
+            }
+            builder.add_labeled_edge(&from, &to, weight);
+        }
+        Ok(builder)
+    }
+}
+
+/// Test that a CSV edge list with a header and custom delimiter builds the expected graph.
+#[test]
+fn test_from_csv_reader() {
+    let csv = "from;to;weight\nA;B;1.5\n\nB ; A ; 2\n";
+    let options = CsvOptions {
+        delimiter: ';',
+        has_header: true,
+    };
+    let graph = GraphBuilder::from_csv_reader_with(csv.as_bytes(), options)
+        .unwrap()
+        .build();
+    assert_eq!(graph.node_labels(), ["A", "B"]);
+    assert_eq!(graph.weight_matrix()[[0, 1]], Some(1.5));
+    assert_eq!(graph.weight_matrix()[[1, 0]], Some(2.0));
+}
+
+/// Test that malformed rows are reported with their line number.
+#[test]
+fn test_from_csv_reader_errors() {
+    let error = GraphBuilder::from_csv_reader("A,B,1\nB,A\n".as_bytes())
+        .err()
+        .unwrap();
+    assert!(matches!(
+        error,
+        ParseError::WrongFieldCount { line: 2, found: 2 }
+    ));
+    let error = GraphBuilder::from_csv_reader("A,B,one\n".as_bytes())
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "line 1: weight \"one\" is not a finite number"
+    );
+    let error = GraphBuilder::from_csv_reader("A,B,1\nB,A,NaN\n".as_bytes())
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "line 2: weight \"NaN\" is not a finite number"
+    );
+    let error = GraphBuilder::from_csv_reader("A,B,inf\nB,A,1\n".as_bytes())
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "line 1: weight \"inf\" is not a finite number"
+    );
+}
+
+/// Test that a custom parser reads a pipe-delimited format, skipping the lines it rejects.
+#[test]
+fn test_read_edges() {
+    let text = "# streets\nA|B|1.5\nB|A|2\nnot an edge\n";
+    let parse = |line: &str| {
+        let mut fields = line.split('|');
+        let (from, to, weight) = (fields.next()?, fields.next()?, fields.next()?);
+        Some((from.to_string(), to.to_string(), weight.parse().ok()?))
+    };
+    let graph = GraphBuilder::read_edges(text.as_bytes(), parse)
+        .unwrap()
+        .build();
+    assert_eq!(graph.node_labels(), ["A", "B"]);
+    assert_eq!(graph.weight_matrix()[[0, 1]], Some(1.5));
+    assert_eq!(graph.weight_matrix()[[1, 0]], Some(2.0));
+
+    let error = GraphBuilder::read_edges("A|B|NaN\n".as_bytes(), parse)
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "line 1: weight \"NaN\" is not a finite number"
+    );
+    let error = GraphBuilder::read_edges("A|B|-inf\n".as_bytes(), parse)
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "line 1: weight \"-inf\" is not a finite number"
+    );
+}
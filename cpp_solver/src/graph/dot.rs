@@ -0,0 +1,51 @@
+use super::Graph;
+use crate::Weight;
+
+impl<W: Weight> Graph<W> {
+    /// Renders the graph in the Graphviz DOT format.
+    ///
+    /// Nodes are declared by index with their label, and every edge, including parallel ones,
+    /// is labeled with its weight.
+    pub fn to_dot(&self) -> String {
+        let mut dot = self.dot_nodes();
+        for edge in &self.edges {
+            dot += &format!(
+                "    {} -> {} [label=\"{}\"];\n",
+                edge.from, edge.to, edge.weight
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Opens a `digraph` and declares every node with its label.
+    pub(crate) fn dot_nodes(&self) -> String {
+        let mut dot = String::from("digraph {\n");
+        for (node, label) in self.node_labels.iter().enumerate() {
+            dot += &format!("    {} [label=\"{}\"];\n", node, escape_label(label));
+        }
+        dot
+    }
+}
+
+/// Escapes a label to be used inside a quoted DOT string.
+fn escape_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Test that the DOT output declares every node and edge.
+#[test]
+fn test_to_dot() {
+    use super::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_labeled_edge("A", "B", 1.5)
+        .add_labeled_edge("B", "\"C\"", 2.0);
+    let dot = builder.build().to_dot();
+    assert!(dot.starts_with("digraph {\n"));
+    assert!(dot.contains("    0 [label=\"A\"];\n"));
+    assert!(dot.contains("    2 [label=\"\\\"C\\\"\"];\n"));
+    assert!(dot.contains("    0 -> 1 [label=\"1.5\"];\n"));
+    assert!(dot.contains("    1 -> 2 [label=\"2\"];\n"));
+    assert!(dot.ends_with("}\n"));
+}
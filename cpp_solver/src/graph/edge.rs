@@ -1,6 +1,8 @@
 /// Represents an edge in the graph, with a source node, target node, and weight.
-pub(super) struct Edge {
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) struct Edge<W> {
     pub(super) from: usize,
     pub(super) to: usize,
-    pub(super) weight: f64,
+    pub(super) weight: W,
 }
@@ -1,88 +1,352 @@
-use ndarray::Array2;
-
 use super::Edge;
 use super::Graph;
-use std::collections::{HashMap, HashSet};
+use super::GraphError;
+use crate::Weight;
+use std::collections::HashMap;
 /// Builder for constructing a graph.
-pub struct GraphBuilder {
-    edges: Vec<Edge>,
-    max_node: usize,
+///
+/// Numeric and labeled nodes share one index space: the first new label names node 0, the
+/// next one node 1, and so on, so `add_edge(0, 1, _)` and `add_labeled_edge("A", "B", _)` add
+/// parallel edges between the same two nodes. Nodes only ever given by index are labeled with
+/// their index.
+#[derive(Clone)]
+pub struct GraphBuilder<W = f64> {
+    pub(super) edges: Vec<Edge<W>>,
+    max_node: Option<usize>,
     node_labels: HashMap<String, usize>,
-    used_labels: HashSet<String>,
+    coordinates: HashMap<usize, (f64, f64)>,
+    /// Whether edges with a negative weight are rejected.
+    forbid_negative: bool,
 }
 
 impl GraphBuilder {
-    /// Creates a new instance of `GraphBuilder`.
+    /// Creates a new instance of `GraphBuilder` with `f64` weights.
+    ///
+    /// Use `GraphBuilder::<W>::default()` for another weight type.
     pub fn new() -> Self {
-        Self {
-            edges: Vec::new(),
-            max_node: 0,
-            node_labels: HashMap::new(),
-            used_labels: HashSet::new(),
-        }
+        Self::default()
+    }
+}
+
+impl<W: Weight> GraphBuilder<W> {
+    /// Sets whether edges with a negative weight are rejected, e.g. for street lengths that
+    /// can't be negative. Defaults to `false`.
+    ///
+    /// Otherwise negative weights are allowed as long as they don't close a negative cycle, which
+    /// the solver reports as `SolveError::NegativeCycle`.
+    pub fn forbid_negative(&mut self, forbid: bool) -> &mut Self {
+        self.forbid_negative = forbid;
+        self
     }
 
     /// Adds an edge to the graph using numeric indices.
-    pub fn add_edge(&mut self, from: usize, to: usize, weight: f64) -> &mut Self {
-        self.max_node = self.max_node.max(from).max(to);
-        self.edges.push(Edge { from, to, weight });
+    ///
+    /// Negative weights are allowed as long as they don't close a negative cycle, which the
+    /// solver reports as `SolveError::NegativeCycle`.
+    ///
+    /// # Panics
+    ///
+    /// If the weight is NaN, since it can't be compared with other weights, or infinite, since a
+    /// missing edge has no weight, or negative while `forbid_negative` is set. Use `try_add_edge` to get these as an error instead.
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: W) -> &mut Self {
+        if let Err(error) = self.try_add_edge(from, to, weight) {
+            panic!("{}", error);
+        }
         self
     }
 
+    /// Adds an edge to the graph using numeric indices, unless its weight is invalid.
+    ///
+    /// # Returns
+    ///
+    /// The builder, or `GraphError::NanWeight` or `GraphError::InfiniteWeight` if the weight is
+    /// not finite, or `GraphError::NegativeWeight` if it is negative while `forbid_negative` is
+    /// set.
+    pub fn try_add_edge(
+        &mut self,
+        from: usize,
+        to: usize,
+        weight: W,
+    ) -> Result<&mut Self, GraphError> {
+        GraphError::check_weight(from, to, weight)?;
+        if self.forbid_negative && weight < W::zero() {
+            return Err(GraphError::NegativeWeight { from, to });
+        }
+        self.max_node = Some(
+            self.max_node
+                .map_or(0, |max_node| max_node.max(from))
+                .max(to),
+        );
+        self.edges.push(Edge { from, to, weight });
+        Ok(self)
+    }
+
     /// Adds an edge to the graph using labeled nodes.
-    pub fn add_labeled_edge(&mut self, from_label: &str, to_label: &str, weight: f64) -> &mut Self {
+    ///
+    /// # Panics
+    ///
+    /// If the weight is invalid, like `add_edge`.
+    pub fn add_labeled_edge(&mut self, from_label: &str, to_label: &str, weight: W) -> &mut Self {
         let from = self.get_or_insert_label(from_label);
         let to = self.get_or_insert_label(to_label);
         self.add_edge(from, to, weight)
     }
 
-    /// Builds the graph from the added edges.
-    pub fn build(self) -> Graph {
-        let n_nodes = if self.max_node > 0 {
-            self.max_node + 1
-        } else {
-            0
+    /// Adds an edge to the graph using labeled nodes, unless its weight is invalid.
+    ///
+    /// A rejected edge adds no nodes, even if its labels are new.
+    ///
+    /// # Returns
+    ///
+    /// The builder, or the error of `try_add_edge`.
+    pub fn try_add_labeled_edge(
+        &mut self,
+        from_label: &str,
+        to_label: &str,
+        weight: W,
+    ) -> Result<&mut Self, GraphError> {
+        let n_labels = self.node_labels.len();
+        let from = self.get_or_insert_label(from_label);
+        let to = self.get_or_insert_label(to_label);
+        if let Err(error) = self.try_add_edge(from, to, weight) {
+            self.node_labels.retain(|_, &mut node| node < n_labels);
+            return Err(error);
+        }
+        Ok(self)
+    }
+
+    /// Adds a labeled node without any edge, e.g. a depot whose streets are added later.
+    ///
+    /// # Returns
+    ///
+    /// The index of the node, which is the existing one if the label was already added.
+    pub fn add_node(&mut self, label: &str) -> usize {
+        let node = self.get_or_insert_label(label);
+        self.max_node = Some(self.max_node.map_or(node, |max_node| max_node.max(node)));
+        node
+    }
+
+    /// Places a labeled node on the map, adding it if needed, e.g. to export the route as GeoJSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label of the node.
+    /// * `lon` - The longitude of the node, in degrees.
+    /// * `lat` - The latitude of the node, in degrees.
+    pub fn add_node_coords(&mut self, label: &str, lon: f64, lat: f64) -> &mut Self {
+        let node = self.add_node(label);
+        self.coordinates.insert(node, (lon, lat));
+        self
+    }
+
+    /// Adds every edge yielded by an iterator, using numeric indices.
+    ///
+    /// Space for the edges is reserved up front from the iterator's size hint.
+    pub fn add_edges<I: IntoIterator<Item = (usize, usize, W)>>(&mut self, edges: I) -> &mut Self {
+        let edges = edges.into_iter();
+        self.edges.reserve(edges.size_hint().0);
+        for (from, to, weight) in edges {
+            self.add_edge(from, to, weight);
+        }
+        self
+    }
+
+    /// Adds every edge yielded by an iterator, using labeled nodes.
+    pub fn add_labeled_edges<'a, I: IntoIterator<Item = (&'a str, &'a str, W)>>(
+        &mut self,
+        edges: I,
+    ) -> &mut Self {
+        let edges = edges.into_iter();
+        self.edges.reserve(edges.size_hint().0);
+        for (from_label, to_label, weight) in edges {
+            self.add_labeled_edge(from_label, to_label, weight);
+        }
+        self
+    }
+
+    /// Adds every edge of another builder, e.g. one reading a second data source.
+    ///
+    /// Labeled nodes of `other` are matched by label, so the same label refers to the same node
+    /// in the merged graph. Unlabeled nodes keep their numeric index. Every labeled node of
+    /// `other` is carried over, even one without edges.
+    pub fn merge(&mut self, other: GraphBuilder<W>) -> &mut Self {
+        let labels = other.sorted_labels();
+        for label in &labels {
+            self.add_node(label);
+        }
+        let mut node = |index: usize| match labels.get(index) {
+            Some(label) => self.get_or_insert_label(label),
+            None => index,
         };
+        let edges: Vec<_> = other
+            .edges
+            .into_iter()
+            .map(|Edge { from, to, weight }| (node(from), node(to), weight))
+            .collect();
+        for (index, (lon, lat)) in other.coordinates {
+            self.add_node_coords(&labels[index], lon, lat);
+        }
+        self.add_edges(edges)
+    }
 
-        // Create a weight matrix initialized to infinity
-        let mut weight_matrix = Array2::from_elem((n_nodes, n_nodes), f64::INFINITY);
+    /// Removes an edge from the graph using numeric indices.
+    ///
+    /// Only the most recently added of several parallel edges is removed. The nodes themselves
+    /// stay in the graph, even when no edge touches them anymore.
+    ///
+    /// # Returns
+    ///
+    /// Whether an edge was removed.
+    pub fn remove_edge(&mut self, from: usize, to: usize) -> bool {
+        match self
+            .edges
+            .iter()
+            .rposition(|edge| edge.from == from && edge.to == to)
+        {
+            Some(index) => {
+                self.edges.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
 
-        // Populate the weight matrix with edges
-        for Edge { from, to, weight } in self.edges {
-            weight_matrix[[from, to]] = weight;
+    /// Removes an edge from the graph using labeled nodes.
+    ///
+    /// # Returns
+    ///
+    /// Whether an edge was removed. Unknown labels remove nothing.
+    pub fn remove_labeled_edge(&mut self, from_label: &str, to_label: &str) -> bool {
+        match (
+            self.node_labels.get(from_label),
+            self.node_labels.get(to_label),
+        ) {
+            (Some(&from), Some(&to)) => self.remove_edge(from, to),
+            _ => false,
         }
+    }
 
-        // Convert node labels map to a sorted vector
-        let mut node_labels = vec![String::new(); self.node_labels.len()];
-        for (label, &index) in &self.node_labels {
-            node_labels[index] = label.clone();
+    /// Adds a two-way edge to the graph using numeric indices.
+    ///
+    /// Both directions get the same weight, so a graph built only from undirected edges is
+    /// balanced by construction. A self-loop is added only once.
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize, weight: W) -> &mut Self {
+        self.add_edge(a, b, weight);
+        if a != b {
+            self.add_edge(b, a, weight);
         }
+        self
+    }
+
+    /// Adds a two-way edge to the graph using labeled nodes.
+    pub fn add_labeled_undirected_edge(
+        &mut self,
+        a_label: &str,
+        b_label: &str,
+        weight: W,
+    ) -> &mut Self {
+        let a = self.get_or_insert_label(a_label);
+        let b = self.get_or_insert_label(b_label);
+        self.add_undirected_edge(a, b, weight)
+    }
+
+    /// Adds a two-way edge whose directions have their own weights, e.g. a street uphill one way.
+    ///
+    /// Both directions are separate directed edges, so `solve` walks each of them at least once
+    /// and prefers the cheaper direction when it walks the street again. Routes walking such a
+    /// street in only one direction, the windy postman problem, are not searched.
+    ///
+    /// # Arguments
+    ///
+    /// * `a`, `b` - The nodes at both ends of the edge.
+    /// * `weight_ab` - The weight from `a` to `b`.
+    /// * `weight_ba` - The weight from `b` to `a`.
+    pub fn add_windy_edge(&mut self, a: usize, b: usize, weight_ab: W, weight_ba: W) -> &mut Self {
+        self.add_edge(a, b, weight_ab);
+        self.add_edge(b, a, weight_ba)
+    }
+
+    /// Adds a two-way edge whose directions have their own weights, using labeled nodes.
+    pub fn add_labeled_windy_edge(
+        &mut self,
+        a_label: &str,
+        b_label: &str,
+        weight_ab: W,
+        weight_ba: W,
+    ) -> &mut Self {
+        let a = self.get_or_insert_label(a_label);
+        let b = self.get_or_insert_label(b_label);
+        self.add_windy_edge(a, b, weight_ab, weight_ba)
+    }
+
+    /// Builds the graph from the added edges.
+    pub fn build(self) -> Graph<W> {
+        let n_nodes = self.n_nodes();
+
+        let mut node_labels = self.sorted_labels();
         let node_labels = if !node_labels.is_empty() {
+            // Label the nodes past the labeled ones, only given by index, like unlabeled graphs
+            node_labels.extend((node_labels.len()..n_nodes).map(|node| node.to_string()));
             Some(node_labels)
         } else {
             None
         };
 
-        // Build the graph using from_weight_matrix
-        Graph::from_weight_matrix(weight_matrix, node_labels)
+        // Start from a graph without edges, then add every edge including parallel ones
+        let mut graph = Graph::without_edges(n_nodes, node_labels);
+        for Edge { from, to, weight } in self.edges {
+            graph.add_edge(from, to, weight);
+        }
+        for (node, coordinates) in self.coordinates {
+            graph.coordinates[node] = Some(coordinates);
+        }
+        graph
+    }
+
+    /// Converts the node labels map to a vector sorted by node index.
+    pub(super) fn sorted_labels(&self) -> Vec<String> {
+        let mut node_labels = vec![String::new(); self.node_labels.len()];
+        for (label, &index) in &self.node_labels {
+            node_labels[index] = label.clone();
+        }
+        node_labels
+    }
+
+    /// Returns the number of nodes the built graph will have.
+    pub(super) fn n_nodes(&self) -> usize {
+        self.max_node.map_or(0, |max_node| max_node + 1)
     }
 
     /// Retrieves or inserts a label into the `node_labels` map.
-    fn get_or_insert_label(&mut self, label: &str) -> usize {
+    pub(super) fn get_or_insert_label(&mut self, label: &str) -> usize {
         if let Some(&index) = self.node_labels.get(label) {
             index
         } else {
             let index = self.node_labels.len();
             self.node_labels.insert(label.to_string(), index);
-            self.used_labels.insert(label.to_string());
             index
         }
     }
 }
 
-impl Default for GraphBuilder {
+/// Collects `(from, to, weight)` edges using numeric indices.
+impl<W: Weight> From<Vec<(usize, usize, W)>> for GraphBuilder<W> {
+    fn from(edges: Vec<(usize, usize, W)>) -> Self {
+        let mut builder = Self::default();
+        builder.add_edges(edges);
+        builder
+    }
+}
+
+impl<W> Default for GraphBuilder<W> {
     fn default() -> Self {
-        Self::new()
+        Self {
+            edges: Vec::new(),
+            max_node: None,
+            node_labels: HashMap::new(),
+            coordinates: HashMap::new(),
+            forbid_negative: false,
+        }
     }
 }
 
@@ -93,10 +357,10 @@ fn test_empty_graph() {
     let graph = builder.build();
     let weight_matrix = &graph.weight_matrix;
 
-    // Ensure the matrix dimensions are zero or all entries are `f64::INFINITY`
+    // Ensure the matrix dimensions are zero or all entries are `None`
     assert!(
         weight_matrix.is_empty(),
-        "Weight matrix should be empty or all entries should be `f64::INFINITY`"
+        "Weight matrix should be empty or all entries should be `None`"
     );
 
     // Check that no node labels are present
@@ -112,11 +376,69 @@ fn test_add_labeled_edge() {
     let mut builder = GraphBuilder::new();
     builder.add_labeled_edge("A", "B", 5.0);
     let graph = builder.build();
-    assert_eq!(graph.weight_matrix[[0, 1]], 5.0);
+    assert_eq!(graph.weight_matrix[[0, 1]], Some(5.0));
     assert_eq!(graph.node_labels[0], "A");
     assert_eq!(graph.node_labels[1], "B");
 }
 
+/// Test that a node added without edges is part of the built graph.
+#[test]
+fn test_add_node() {
+    let mut builder = GraphBuilder::new();
+    builder.add_labeled_edge("A", "B", 5.0);
+    assert_eq!(builder.add_node("Depot"), 2);
+    assert_eq!(builder.add_node("A"), 0);
+    let graph = builder.build();
+    assert_eq!(graph.node_labels, ["A", "B", "Depot"]);
+    assert!(graph.weight_matrix.row(2).iter().all(Option::is_none));
+    assert!(graph.weight_matrix.column(2).iter().all(Option::is_none));
+}
+
+/// Test that numeric and labeled edges in one builder refer to the same nodes.
+#[test]
+fn test_mixed_numeric_and_labeled_edges() {
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_labeled_edge("A", "B", 2.0)
+        .add_edge(1, 3, 3.0)
+        .add_labeled_edge("C", "A", 4.0);
+    let graph = builder.build();
+    assert_eq!(graph.node_labels, ["A", "B", "C", "3"]);
+    assert_eq!(
+        graph.weighted_edge_set(),
+        vec![
+            vec![(1, 1.0), (1, 2.0)],
+            vec![(3, 3.0)],
+            vec![(0, 4.0)],
+            vec![]
+        ]
+    );
+}
+
+/// Test that the edges touching imbalanced nodes are listed in the order they were added.
+#[test]
+fn test_unbalanced_edges() {
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(1, 0, 1.0)
+        .add_edge(1, 2, 1.0)
+        .add_edge(2, 3, 1.0)
+        .add_edge(3, 2, 1.0);
+    let graph = builder.build();
+    assert_eq!(
+        graph.unbalanced_edges(),
+        [(0, 1), (1, 0), (1, 2), (2, 3), (3, 2)]
+    );
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(1, 0, 1.0)
+        .add_edge(2, 2, 1.0);
+    assert!(builder.build().unbalanced_edges().is_empty());
+}
+
 /// Test that out-in degree difference calculations work correctly for balanced graphs.
 #[test]
 fn test_out_in_diff() {
@@ -126,3 +448,206 @@ fn test_out_in_diff() {
     let imbalanced_nodes = graph.imbalanced_nodes();
     assert!(imbalanced_nodes.is_empty());
 }
+
+/// Test that an adjacency list builds the same graph as the equivalent builder calls.
+#[test]
+fn test_from_adjacency_list() {
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(0, 2, 4.0)
+        .add_edge(1, 2, 2.0)
+        .add_edge(2, 0, 3.0);
+    let expected = builder.build();
+    let graph = Graph::from_adjacency_list(
+        vec![vec![(1, 1.0), (2, 4.0)], vec![(2, 2.0)], vec![(0, 3.0)]],
+        None,
+    );
+    assert_eq!(graph.weight_matrix, expected.weight_matrix);
+    assert_eq!(graph.node_labels, expected.node_labels);
+    assert_eq!(graph.out_degrees(), expected.out_degrees());
+    assert_eq!(graph.edges, expected.edges);
+}
+
+/// Test that parallel adjacency entries are counted as parallel edges.
+#[test]
+fn test_from_adjacency_list_parallel_edges() {
+    let graph = Graph::from_adjacency_list(vec![vec![(1, 1.0), (1, 1.0)], vec![(0, 1.0)]], None);
+    assert_eq!(graph.edge_set()[0], vec![1, 1]);
+    assert_eq!(graph.out_degrees()[0], 2);
+}
+
+/// Test that parallel edges keep their own weights, and the matrix holds the cheapest one.
+#[test]
+fn test_parallel_edges() {
+    let mut builder = GraphBuilder::new();
+    builder.add_edge(0, 1, 5.0).add_edge(0, 1, 3.0);
+    let graph = builder.build();
+    assert_eq!(graph.weight_matrix[[0, 1]], Some(3.0));
+    assert_eq!(graph.weighted_edge_set()[0], vec![(1, 5.0), (1, 3.0)]);
+    assert_eq!(graph.out_degrees()[0], 2);
+    assert_eq!(graph.in_degrees()[1], 2);
+}
+
+/// Test that an undirected edge adds both directions with the same weight.
+#[test]
+fn test_add_undirected_edge() {
+    let mut builder = GraphBuilder::new();
+    builder.add_labeled_undirected_edge("A", "B", 3.0);
+    let graph = builder.build();
+    assert_eq!(graph.weight_matrix[[0, 1]], Some(3.0));
+    assert_eq!(graph.weight_matrix[[1, 0]], Some(3.0));
+    assert!(graph.imbalanced_nodes().is_empty());
+}
+
+/// Test that a windy edge adds both directions with their own weights.
+#[test]
+fn test_add_windy_edge() {
+    let mut builder = GraphBuilder::new();
+    builder.add_labeled_windy_edge("A", "B", 1.0, 4.0);
+    let graph = builder.build();
+    assert_eq!(graph.weight_matrix[[0, 1]], Some(1.0));
+    assert_eq!(graph.weight_matrix[[1, 0]], Some(4.0));
+    assert!(graph.imbalanced_nodes().is_empty());
+}
+
+/// Test that removing an edge takes away only one of several parallel edges.
+#[test]
+fn test_remove_edge() {
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_labeled_edge("A", "B", 1.0)
+        .add_labeled_edge("A", "B", 2.0)
+        .add_labeled_edge("B", "A", 3.0);
+    assert!(builder.remove_labeled_edge("A", "B"));
+    assert!(!builder.remove_labeled_edge("A", "C"));
+    assert!(!builder.remove_edge(0, 0));
+    let graph = builder.build();
+    assert_eq!(graph.weighted_edge_set()[0], vec![(1, 1.0)]);
+    assert_eq!(graph.out_degrees()[0], 1);
+}
+
+/// Test that a graph whose only edge is a self-loop at node 0 still has that node.
+#[test]
+fn test_self_loop_at_first_node() {
+    let mut builder = GraphBuilder::new();
+    builder.add_edge(0, 0, 2.0);
+    let graph = builder.build();
+    assert_eq!(graph.node_count(), 1);
+    assert_eq!(graph.weight_matrix[[0, 0]], Some(2.0));
+
+    let mut builder = GraphBuilder::new();
+    builder.add_labeled_edge("A", "A", 2.0);
+    let graph = builder.build();
+    assert_eq!(graph.node_labels, ["A"]);
+    assert_eq!(graph.weight_matrix[[0, 0]], Some(2.0));
+    assert_eq!(graph.edge_count(), 1);
+}
+
+/// Test that adding edges in bulk builds the same graph as chained `add_edge` calls.
+#[test]
+fn test_add_edges() {
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_edge(0, 1, 1.0)
+        .add_edge(1, 2, 2.0)
+        .add_edge(2, 0, 3.0);
+    let expected = builder.build();
+
+    let mut builder = GraphBuilder::new();
+    builder.add_edges(vec![(0, 1, 1.0), (1, 2, 2.0), (2, 0, 3.0)]);
+    let graph = builder.build();
+    assert_eq!(graph.weight_matrix, expected.weight_matrix);
+    assert_eq!(graph.edges, expected.edges);
+
+    let mut builder = GraphBuilder::new();
+    builder.add_labeled_edges(vec![("A", "B", 1.0), ("B", "C", 2.0), ("C", "A", 3.0)]);
+    let graph = builder.build();
+    assert_eq!(graph.weight_matrix, expected.weight_matrix);
+    assert_eq!(graph.node_labels, ["A", "B", "C"]);
+}
+
+/// Test that a NaN weight is rejected when the edge is added.
+#[test]
+#[should_panic(expected = "the edge from 0 to 1 has a NaN weight")]
+fn test_add_edge_nan_weight() {
+    GraphBuilder::new().add_edge(0, 1, f64::NAN);
+}
+
+/// Test that invalid weights are returned as errors by the fallible builder methods.
+#[test]
+fn test_try_add_edge() {
+    let mut builder = GraphBuilder::new();
+    builder.add_labeled_edge("A", "B", 1.0);
+    assert_eq!(
+        builder.try_add_edge(0, 1, f64::NAN).err(),
+        Some(GraphError::NanWeight { from: 0, to: 1 })
+    );
+    assert_eq!(
+        builder.try_add_labeled_edge("B", "C", f64::NAN).err(),
+        Some(GraphError::NanWeight { from: 1, to: 2 })
+    );
+    assert_eq!(
+        builder.try_add_edge(1, 0, f64::INFINITY).err(),
+        Some(GraphError::InfiniteWeight { from: 1, to: 0 })
+    );
+    assert_eq!(
+        builder
+            .try_add_labeled_edge("C", "A", f64::NEG_INFINITY)
+            .err(),
+        Some(GraphError::InfiniteWeight { from: 2, to: 0 })
+    );
+    // A valid negative weight still solves
+    builder.try_add_labeled_edge("B", "A", -0.5).unwrap();
+    builder.forbid_negative(true);
+    assert_eq!(
+        builder.try_add_labeled_edge("A", "B", -1.0).err(),
+        Some(GraphError::NegativeWeight { from: 0, to: 1 })
+    );
+    builder.try_add_edge(0, 1, 2.0).unwrap();
+
+    let graph = builder.build();
+    assert_eq!(graph.node_labels, ["A", "B"]);
+    assert_eq!(graph.edge_count(), 3);
+    assert!(crate::CppSolver::new(graph)
+        .solve()
+        .unwrap()
+        .cost_approx_eq(2.0, crate::DEFAULT_COST_EPSILON));
+}
+
+/// Test that a negative weight panics while the builder forbids them.
+#[test]
+#[should_panic(expected = "the edge from 0 to 1 has a negative weight")]
+fn test_add_edge_forbidden_negative_weight() {
+    GraphBuilder::new()
+        .forbid_negative(true)
+        .add_edge(0, 1, -1.0);
+}
+
+/// Test that merging builders matches nodes by label and keeps the edges and nodes of both.
+#[test]
+fn test_merge() {
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_labeled_edge("A", "B", 1.0)
+        .add_labeled_edge("B", "C", 2.0);
+    let mut other = GraphBuilder::new();
+    other
+        .add_labeled_edge("C", "A", 3.0)
+        .add_labeled_edge("D", "B", 4.0)
+        .add_labeled_edge("A", "B", 5.0);
+    other.add_node("E");
+    builder.merge(other);
+    let graph = builder.build();
+    assert_eq!(graph.node_labels, ["A", "B", "C", "D", "E"]);
+    assert_eq!(
+        graph.weighted_edge_set(),
+        vec![
+            vec![(1, 1.0), (1, 5.0)],
+            vec![(2, 2.0)],
+            vec![(0, 3.0)],
+            vec![(1, 4.0)],
+            vec![]
+        ]
+    );
+}
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+
+use super::Graph;
+use crate::Weight;
+
+/// Represents the differences between two graphs, with edges identified by node labels.
+#[derive(Debug, PartialEq)]
+pub struct GraphDiff<W = f64> {
+    /// Edges only present in the other graph, as `(from, to, weight)`.
+    pub added: Vec<(String, String, W)>,
+    /// Edges only present in the original graph, as `(from, to, weight)`.
+    pub removed: Vec<(String, String, W)>,
+    /// Edges present in both graphs with different weights, as `(from, to, old, new)`.
+    pub reweighted: Vec<(String, String, W, W)>,
+}
+
+impl<W: Weight> GraphDiff<W> {
+    /// Compares two graphs, listing the differences sorted by label.
+    pub(super) fn new(original: &Graph<W>, other: &Graph<W>) -> Self {
+        let original_edges = Self::labeled_edges(original);
+        let mut other_edges = Self::labeled_edges(other);
+        let mut diff = Self {
+            added: Vec::new(),
+            removed: Vec::new(),
+            reweighted: Vec::new(),
+        };
+
+        for ((from, to), weight) in original_edges {
+            match other_edges.remove(&(from.clone(), to.clone())) {
+                Some(new_weight) if new_weight != weight => {
+                    diff.reweighted.push((from, to, weight, new_weight))
+                }
+                Some(_) => (),
+                None => diff.removed.push((from, to, weight)),
+            }
+        }
+        diff.added = other_edges
+            .into_iter()
+            .map(|((from, to), weight)| (from, to, weight))
+            .collect();
+        diff
+    }
+
+    /// Collects the edges of a graph keyed by the labels of their endpoints.
+    fn labeled_edges(graph: &Graph<W>) -> BTreeMap<(String, String), W> {
+        graph
+            .weight_matrix
+            .indexed_iter()
+            .filter_map(|((from, to), &weight)| {
+                Some((
+                    (
+                        graph.node_labels[from].clone(),
+                        graph.node_labels[to].clone(),
+                    ),
+                    weight?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Checks if the two graphs have the same edges and weights.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.reweighted.is_empty()
+    }
+}
+
+/// Test that added, removed, and reweighted edges are reported by label.
+#[test]
+fn test_graph_diff() {
+    use crate::GraphBuilder;
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_labeled_edge("a", "b", 1.0)
+        .add_labeled_edge("b", "c", 2.0)
+        .add_labeled_edge("c", "a", 3.0);
+    let original = builder.build();
+
+    // Labels are inserted in a different order, so the indices differ from the original.
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_labeled_edge("c", "a", 4.0)
+        .add_labeled_edge("a", "b", 1.0)
+        .add_labeled_edge("b", "a", 5.0);
+    let other = builder.build();
+
+    let diff = original.diff(&other);
+    assert_eq!(diff.added, vec![("b".to_string(), "a".to_string(), 5.0)]);
+    assert_eq!(diff.removed, vec![("b".to_string(), "c".to_string(), 2.0)]);
+    assert_eq!(
+        diff.reweighted,
+        vec![("c".to_string(), "a".to_string(), 3.0, 4.0)]
+    );
+    assert!(original.diff(&original).is_empty());
+}
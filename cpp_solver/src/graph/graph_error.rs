@@ -0,0 +1,76 @@
+use crate::Weight;
+use std::{error::Error, fmt};
+
+/// Represents the reasons a graph can't be built or changed as requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    /// The matrix doesn't have as many columns as rows.
+    NotSquare { rows: usize, cols: usize },
+    /// An entry of the matrix is NaN.
+    NanWeight { from: usize, to: usize },
+    /// An edge has an infinite weight. A missing edge has no weight instead.
+    InfiniteWeight { from: usize, to: usize },
+    /// An edge has a negative weight, but the builder forbids them.
+    NegativeWeight { from: usize, to: usize },
+    /// The number of labels differs from the number of nodes.
+    LabelCountMismatch { expected: usize, found: usize },
+    /// A node index is not less than the number of nodes.
+    IndexOutOfRange { index: usize, n_nodes: usize },
+    /// A node has no coordinates, but the output needs them.
+    MissingCoordinates { label: String },
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GraphError::NotSquare { rows, cols } => write!(
+                f,
+                "the weight matrix has {} rows but {} columns",
+                rows, cols
+            ),
+            GraphError::NanWeight { from, to } => {
+                write!(f, "the edge from {} to {} has a NaN weight", from, to)
+            }
+            GraphError::InfiniteWeight { from, to } => {
+                write!(f, "the edge from {} to {} has an infinite weight", from, to)
+            }
+            GraphError::NegativeWeight { from, to } => {
+                write!(f, "the edge from {} to {} has a negative weight", from, to)
+            }
+            GraphError::LabelCountMismatch { expected, found } => {
+                write!(
+                    f,
+                    "expected {} labels, one per node, found {}",
+                    expected, found
+                )
+            }
+            GraphError::IndexOutOfRange { index, n_nodes } => write!(
+                f,
+                "node {} is out of range for a graph with {} nodes",
+                index, n_nodes
+            ),
+            GraphError::MissingCoordinates { label } => {
+                write!(f, "node {} has no coordinates", label)
+            }
+        }
+    }
+}
+
+impl GraphError {
+    /// Checks that an edge weight is finite, as every edge of a graph must be.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())`, or `GraphError::NanWeight` or `GraphError::InfiniteWeight` for the edge.
+    pub(crate) fn check_weight<W: Weight>(from: usize, to: usize, weight: W) -> Result<(), Self> {
+        if weight.is_nan() {
+            Err(GraphError::NanWeight { from, to })
+        } else if weight.is_infinite() {
+            Err(GraphError::InfiniteWeight { from, to })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Error for GraphError {}
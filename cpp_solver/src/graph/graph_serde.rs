@@ -0,0 +1,107 @@
+use super::{Edge, Graph, GraphBuilder};
+use crate::Weight;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Flat representation shared by `Graph` and `GraphBuilder`, so the JSON stays human-editable.
+///
+/// Parallel edges are listed once each, and nodes without a label are left out of `node_labels`.
+#[derive(Serialize, Deserialize)]
+struct EdgeList<W> {
+    n_nodes: usize,
+    node_labels: Vec<String>,
+    edges: Vec<Edge<W>>,
+}
+
+impl<W: Weight + Serialize> Serialize for Graph<W> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EdgeList {
+            n_nodes: self.weight_matrix.nrows(),
+            node_labels: self.node_labels.clone(),
+            edges: self.edges.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, W: Weight + Deserialize<'de>> Deserialize<'de> for Graph<W> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let EdgeList {
+            n_nodes,
+            node_labels,
+            edges,
+        } = EdgeList::deserialize(deserializer)?;
+        if node_labels.len() != n_nodes {
+            return Err(serde::de::Error::invalid_length(
+                node_labels.len(),
+                &"one label per node",
+            ));
+        }
+        if let Some(edge) = edges.iter().find(|edge| edge.from.max(edge.to) >= n_nodes) {
+            return Err(serde::de::Error::custom(format!(
+                "edge {} -> {} refers to a node outside the graph",
+                edge.from, edge.to
+            )));
+        }
+        let mut graph = Graph::without_edges(n_nodes, Some(node_labels));
+        for Edge { from, to, weight } in edges {
+            graph
+                .try_add_edge(from, to, weight)
+                .map_err(serde::de::Error::custom)?;
+        }
+        Ok(graph)
+    }
+}
+
+impl<W: Weight + Serialize> Serialize for GraphBuilder<W> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EdgeList {
+            n_nodes: self.n_nodes(),
+            node_labels: self.sorted_labels(),
+            edges: self.edges.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, W: Weight + Deserialize<'de>> Deserialize<'de> for GraphBuilder<W> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let EdgeList {
+            node_labels, edges, ..
+        } = EdgeList::deserialize(deserializer)?;
+        let mut builder = GraphBuilder::default();
+        for label in &node_labels {
+            builder.get_or_insert_label(label);
+        }
+        for Edge { from, to, weight } in edges {
+            builder
+                .try_add_edge(from, to, weight)
+                .map_err(serde::de::Error::custom)?;
+        }
+        Ok(builder)
+    }
+}
+
+/// Test that a graph survives a JSON round trip and still solves to the same cost.
+#[test]
+fn test_graph_json_round_trip() {
+    use crate::{CppSolver, DEFAULT_COST_EPSILON};
+    let mut builder = GraphBuilder::new();
+    builder
+        .add_labeled_edge("A", "B", 1.0)
+        .add_labeled_edge("B", "C", 2.0)
+        .add_labeled_edge("C", "A", 3.0)
+        .add_labeled_edge("A", "C", 4.0);
+    let json = serde_json::to_string(&builder).unwrap();
+    let graph = serde_json::from_str::<GraphBuilder>(&json).unwrap().build();
+    let original = builder.build();
+    let expected = CppSolver::new(original.clone()).solve().unwrap().cost;
+
+    let json = serde_json::to_string(&graph).unwrap();
+    let graph: Graph = serde_json::from_str(&json).unwrap();
+    assert_eq!(graph, original);
+    assert_eq!(graph.node_labels(), ["A", "B", "C"]);
+    assert!(CppSolver::new(graph)
+        .solve()
+        .unwrap()
+        .cost_approx_eq(expected, DEFAULT_COST_EPSILON));
+}
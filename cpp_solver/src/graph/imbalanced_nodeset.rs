@@ -1,8 +1,10 @@
 /// Represents a set of imbalanced nodes, with negative and positive imbalances.
 #[derive(Debug)]
-pub(crate) struct ImbalancedNodeSet {
-    pub(crate) negative: Vec<usize>,
-    pub(crate) positive: Vec<usize>,
+pub struct ImbalancedNodeSet {
+    /// The nodes with more incoming than outgoing edges, once per missing outgoing edge.
+    pub negative: Vec<usize>,
+    /// The nodes with more outgoing than incoming edges, once per missing incoming edge.
+    pub positive: Vec<usize>,
 }
 
 impl ImbalancedNodeSet {
@@ -0,0 +1,46 @@
+use std::{error::Error, fmt, io};
+
+/// Represents the reasons an edge list can't be read into a graph.
+///
+/// Line numbers start at 1 and count the header row, if any.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The underlying reader failed.
+    Io { line: usize, source: io::Error },
+    /// A row doesn't have exactly the `from`, `to`, and `weight` fields.
+    WrongFieldCount { line: usize, found: usize },
+    /// A row has an empty node label.
+    EmptyLabel { line: usize },
+    /// A row's weight is not a finite number.
+    InvalidWeight { line: usize, weight: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io { line, source } => write!(f, "line {}: {}", line, source),
+            ParseError::WrongFieldCount { line, found } => write!(
+                f,
+                "line {}: expected 3 fields (from, to, weight), found {}",
+                line, found
+            ),
+            ParseError::EmptyLabel { line } => write!(f, "line {}: empty node label", line),
+            ParseError::InvalidWeight { line, weight } => {
+                write!(
+                    f,
+                    "line {}: weight {:?} is not a finite number",
+                    line, weight
+                )
+            }
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
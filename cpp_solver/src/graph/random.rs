@@ -0,0 +1,58 @@
+use super::Graph;
+use rand::{rngs::StdRng, seq::SliceRandom, RngExt, SeedableRng};
+use std::ops::Range;
+
+impl Graph {
+    /// Generates a random strongly connected graph, e.g. for benchmarks and property tests.
+    ///
+    /// The nodes are first joined by one cycle in a random order, so every node can reach every
+    /// other node. Every other ordered pair of distinct nodes then gets an edge with the given
+    /// probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_nodes` - The number of nodes.
+    /// * `edge_probability` - The probability of an edge between two nodes, besides the cycle.
+    /// * `weight_range` - The range the weights are drawn from uniformly.
+    /// * `seed` - The seed of the random generator, so the same arguments yield the same graph.
+    pub fn random_connected(
+        n_nodes: usize,
+        edge_probability: f64,
+        weight_range: Range<f64>,
+        seed: u64,
+    ) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut graph = Self::without_edges(n_nodes, None);
+
+        let mut order: Vec<usize> = (0..n_nodes).collect();
+        order.shuffle(&mut rng);
+        for (i, &from) in order.iter().enumerate() {
+            let to = order[(i + 1) % n_nodes];
+            graph.add_edge(from, to, rng.random_range(weight_range.clone()));
+        }
+
+        for from in 0..n_nodes {
+            for to in 0..n_nodes {
+                if from != to && rng.random_bool(edge_probability) {
+                    graph.add_edge(from, to, rng.random_range(weight_range.clone()));
+                }
+            }
+        }
+        graph
+    }
+}
+
+/// Test that a generated graph is strongly connected and reproducible from its seed.
+#[test]
+fn test_random_connected() {
+    use crate::CppSolver;
+    let graph = Graph::random_connected(30, 0.05, 1.0..10.0, 7);
+    assert_eq!(graph.node_count(), 30);
+    assert_eq!(graph.strongly_connected_components().len(), 1);
+
+    let same = Graph::random_connected(30, 0.05, 1.0..10.0, 7);
+    assert!(graph.diff(&same).is_empty());
+
+    // The default strategy checks connectivity with Floyd-Warshall
+    assert!(CppSolver::new(graph).solve().is_ok());
+}
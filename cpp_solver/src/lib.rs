@@ -1,8 +1,32 @@
 mod cpp_solver;
 mod graph;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod weight;
+#[cfg(feature = "bench")]
+pub use cpp_solver::bench;
 pub use cpp_solver::CppSolver;
+pub use cpp_solver::DistanceOracle;
+pub use cpp_solver::MatchingStrategy;
+pub use cpp_solver::Path;
+pub use cpp_solver::ShortestPathStrategy;
+pub use cpp_solver::Solution;
+pub use cpp_solver::SolveError;
+pub use cpp_solver::SolveObserver;
+pub use cpp_solver::SolverConfig;
+pub use cpp_solver::DEFAULT_COST_EPSILON;
+pub use graph::CsvOptions;
 pub use graph::Graph;
 pub use graph::GraphBuilder;
+pub use graph::GraphDiff;
+pub use graph::GraphError;
+pub use graph::ImbalancedNodeSet;
+pub use graph::ParseError;
+#[cfg(feature = "wasm")]
+pub use wasm::WasmSolver;
+pub use weight::Weight;
 
 #[cfg(test)]
 mod integration_tests {
@@ -12,10 +36,31 @@ mod integration_tests {
         let graph = graph_builder.build();
         let mut solver = CppSolver::new(graph);
         match solver.solve() {
-            Some(path) => {
-                assert_eq!(path.cost, cost)
+            Ok(path) => {
+                assert!(
+                    path.cost_approx_eq(cost, DEFAULT_COST_EPSILON),
+                    "expected cost {}, found {}",
+                    cost,
+                    path.cost
+                )
             }
-            None => panic!("No solution found"),
+            Err(err) => panic!("No solution found: {}", err),
+        }
+    }
+
+    fn check_undirected_path(graph_builder: GraphBuilder, cost: f64) {
+        let graph = graph_builder.build();
+        let mut solver = CppSolver::new(graph);
+        match solver.solve_undirected() {
+            Ok(path) => {
+                assert!(
+                    path.cost_approx_eq(cost, DEFAULT_COST_EPSILON),
+                    "expected cost {}, found {}",
+                    cost,
+                    path.cost
+                )
+            }
+            Err(err) => panic!("No solution found: {}", err),
         }
     }
 
@@ -46,6 +91,434 @@ mod integration_tests {
         check_path(graph_builder, 276.);
     }
 
+    #[test]
+    fn test_is_eulerian() {
+        let mut graph_builder = GraphBuilder::new();
+        for i in 0..5 {
+            graph_builder.add_edge(i, (i + 1) % 5, 1.);
+        }
+        let circle = graph_builder.build();
+        assert!(circle.is_balanced());
+        assert!(circle.is_eulerian());
+
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 2, 20.)
+            .add_edge(0, 1, 10.)
+            .add_edge(1, 4, 10.)
+            .add_edge(1, 3, 50.)
+            .add_edge(2, 4, 33.)
+            .add_edge(2, 3, 20.)
+            .add_edge(3, 4, 5.)
+            .add_edge(3, 5, 12.)
+            .add_edge(4, 0, 12.)
+            .add_edge(4, 5, 1.)
+            .add_edge(5, 2, 22.);
+        let standard = graph_builder.build();
+        assert!(!standard.is_balanced());
+        assert!(!standard.is_eulerian());
+
+        // Two separate cycles are balanced, but not connected
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 1, 1.)
+            .add_edge(1, 0, 1.)
+            .add_edge(2, 3, 1.)
+            .add_edge(3, 2, 1.);
+        let cycles = graph_builder.build();
+        assert!(cycles.is_balanced());
+        assert!(!cycles.is_eulerian());
+    }
+
+    #[test]
+    fn test_shortest_path_between() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 2, 20.)
+            .add_edge(0, 1, 10.)
+            .add_edge(1, 4, 10.)
+            .add_edge(1, 3, 50.)
+            .add_edge(2, 4, 33.)
+            .add_edge(2, 3, 20.)
+            .add_edge(3, 4, 5.)
+            .add_edge(3, 5, 12.)
+            .add_edge(4, 0, 12.)
+            .add_edge(4, 5, 1.)
+            .add_edge(5, 2, 22.)
+            .add_edge(6, 6, 1.);
+        let solver = CppSolver::new(graph_builder.build());
+        assert_eq!(
+            solver.shortest_path_between(5, 1),
+            Some(vec![5, 2, 3, 4, 0, 1])
+        );
+        assert_eq!(solver.shortest_path_between(3, 3), Some(vec![3]));
+        assert_eq!(solver.shortest_path_between(0, 6), None);
+        assert_eq!(solver.shortest_path_between(0, 7), None);
+    }
+
+    #[test]
+    fn test_standard_is_circuit() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 2, 20.)
+            .add_edge(0, 1, 10.)
+            .add_edge(1, 4, 10.)
+            .add_edge(1, 3, 50.)
+            .add_edge(2, 4, 33.)
+            .add_edge(2, 3, 20.)
+            .add_edge(3, 4, 5.)
+            .add_edge(3, 5, 12.)
+            .add_edge(4, 0, 12.)
+            .add_edge(4, 5, 1.)
+            .add_edge(5, 2, 22.);
+        let path = CppSolver::new(graph_builder.build()).solve().unwrap();
+        assert!(path.is_circuit());
+    }
+
+    #[test]
+    fn test_required_edge_cost() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 2, 20.)
+            .add_edge(0, 1, 10.)
+            .add_edge(1, 4, 10.)
+            .add_edge(1, 3, 50.)
+            .add_edge(2, 4, 33.)
+            .add_edge(2, 3, 20.)
+            .add_edge(3, 4, 5.)
+            .add_edge(3, 5, 12.)
+            .add_edge(4, 0, 12.)
+            .add_edge(4, 5, 1.)
+            .add_edge(5, 2, 22.);
+        let graph = graph_builder.build();
+        let required_edge_cost = graph.required_edge_cost();
+        assert!((required_edge_cost - 195.).abs() <= DEFAULT_COST_EPSILON);
+        let path = CppSolver::new(graph).solve().unwrap();
+        assert!(path.cost_approx_eq(
+            required_edge_cost + path.balancing_cost,
+            DEFAULT_COST_EPSILON
+        ));
+    }
+
+    #[test]
+    fn test_subgraph() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 2, 20.)
+            .add_edge(0, 1, 10.)
+            .add_edge(1, 4, 10.)
+            .add_edge(1, 3, 50.)
+            .add_edge(2, 4, 33.)
+            .add_edge(2, 3, 20.)
+            .add_edge(3, 4, 5.)
+            .add_edge(3, 5, 12.)
+            .add_edge(4, 0, 12.)
+            .add_edge(4, 5, 1.)
+            .add_edge(5, 2, 22.);
+        let graph = graph_builder.build();
+        let subgraph = graph.subgraph(&[4, 2, 3]);
+        assert_eq!(subgraph.node_labels(), ["4", "2", "3"]);
+        assert_eq!(
+            subgraph.weight_matrix(),
+            ndarray::arr2(&[
+                [None, None, None],
+                [Some(33.), None, Some(20.)],
+                [Some(5.), None, None],
+            ])
+        );
+        assert_eq!(subgraph.edge_count(), 3);
+        assert_eq!(subgraph.out_degrees().to_vec(), [0, 2, 1]);
+    }
+
+    #[test]
+    fn test_edge_traversal_counts() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 2, 20.)
+            .add_edge(0, 1, 10.)
+            .add_edge(1, 4, 10.)
+            .add_edge(1, 3, 50.)
+            .add_edge(2, 4, 33.)
+            .add_edge(2, 3, 20.)
+            .add_edge(3, 4, 5.)
+            .add_edge(3, 5, 12.)
+            .add_edge(4, 0, 12.)
+            .add_edge(4, 5, 1.)
+            .add_edge(5, 2, 22.);
+        let graph = graph_builder.build();
+        let mut solver = CppSolver::new(graph.clone());
+        let path = solver.solve().unwrap();
+        let counts = path.edge_traversal_counts();
+        assert_eq!(counts.values().sum::<usize>(), path.path.len() - 1);
+
+        // Each edge is walked once, plus once more per time balancing repeated it
+        let mut expected: std::collections::HashMap<_, _> = graph
+            .edge_set()
+            .iter()
+            .enumerate()
+            .flat_map(|(from, tos)| tos.iter().map(move |&to| ((from, to), 1)))
+            .collect();
+        for (from, to) in solver.balancing_edges() {
+            let edge = (
+                graph.index_of_label(&from).unwrap(),
+                graph.index_of_label(&to).unwrap(),
+            );
+            *expected.get_mut(&edge).unwrap() += 1;
+        }
+        assert!(expected.values().any(|&count| count == 2));
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 2, 20.)
+            .add_edge(0, 1, 10.)
+            .add_edge(1, 4, 10.)
+            .add_edge(1, 3, 50.)
+            .add_edge(2, 4, 33.)
+            .add_edge(2, 3, 20.)
+            .add_edge(3, 4, 5.)
+            .add_edge(3, 5, 12.)
+            .add_edge(4, 0, 12.)
+            .add_edge(4, 5, 1.)
+            .add_edge(5, 2, 22.)
+            .add_edge(3, 4, 7.);
+        let graph = graph_builder.build();
+        let neighbors: Vec<_> = graph.neighbors(3).collect();
+        assert_eq!(neighbors, [(4, 5.), (5, 12.), (4, 7.)]);
+        assert_eq!(graph.neighbors(3).count(), graph.out_degree(3));
+        assert_eq!(graph.neighbors(6).count(), 0);
+    }
+
+    #[test]
+    fn test_solve_observer() {
+        #[derive(Default)]
+        struct Recorder {
+            imbalanced: usize,
+            matches: Vec<(usize, usize, f64)>,
+            balancing_paths: usize,
+            euler_path: std::collections::VecDeque<usize>,
+        }
+
+        impl SolveObserver for Recorder {
+            fn on_imbalance(&mut self, imbalanced_nodes: &ImbalancedNodeSet) {
+                self.imbalanced = imbalanced_nodes.negative.len();
+            }
+
+            fn on_match(&mut self, from: usize, to: usize, cost: f64) {
+                self.matches.push((from, to, cost));
+            }
+
+            fn on_balancing_path(&mut self, _path: &[usize]) {
+                self.balancing_paths += 1;
+            }
+
+            fn on_euler_path(&mut self, path: &std::collections::VecDeque<usize>) {
+                self.euler_path = path.clone();
+            }
+        }
+
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 2, 20.)
+            .add_edge(0, 1, 10.)
+            .add_edge(1, 4, 10.)
+            .add_edge(1, 3, 50.)
+            .add_edge(2, 4, 33.)
+            .add_edge(2, 3, 20.)
+            .add_edge(3, 4, 5.)
+            .add_edge(3, 5, 12.)
+            .add_edge(4, 0, 12.)
+            .add_edge(4, 5, 1.)
+            .add_edge(5, 2, 22.);
+        let mut solver = CppSolver::new(graph_builder.build());
+        let mut recorder = Recorder::default();
+        let path = solver.solve_with_observer(&mut recorder).unwrap();
+        assert_eq!(recorder.imbalanced, 2);
+        assert_eq!(recorder.matches.len(), 2);
+        assert_eq!(recorder.balancing_paths, 2);
+        let matched_cost: f64 = recorder.matches.iter().map(|&(_, _, cost)| cost).sum();
+        assert!((matched_cost - path.balancing_cost).abs() <= DEFAULT_COST_EPSILON);
+        assert_eq!(recorder.euler_path, path.path);
+    }
+
+    #[test]
+    fn test_solve_detailed() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 2, 20.)
+            .add_edge(0, 1, 10.)
+            .add_edge(1, 4, 10.)
+            .add_edge(1, 3, 50.)
+            .add_edge(2, 4, 33.)
+            .add_edge(2, 3, 20.)
+            .add_edge(3, 4, 5.)
+            .add_edge(3, 5, 12.)
+            .add_edge(4, 0, 12.)
+            .add_edge(4, 5, 1.)
+            .add_edge(5, 2, 22.);
+        let mut solver = CppSolver::new(graph_builder.build());
+        let solution = solver.solve_detailed().unwrap();
+        assert!((solution.total_cost - 276.).abs() <= DEFAULT_COST_EPSILON);
+        assert!((solution.required_cost - 195.).abs() <= DEFAULT_COST_EPSILON);
+        assert!((solution.deadhead_cost - 81.).abs() <= DEFAULT_COST_EPSILON);
+        assert_eq!(solution.n_edges_traversed, solution.path.path.len() - 1);
+        assert_eq!(solution.start, 0);
+        assert!(solution.is_circuit);
+        assert!(solution
+            .path
+            .cost_approx_eq(solution.total_cost, DEFAULT_COST_EPSILON));
+    }
+
+    #[test]
+    fn test_integer_weights() {
+        let mut graph_builder = GraphBuilder::<i64>::default();
+        graph_builder
+            .add_edge(0, 2, 20)
+            .add_edge(0, 1, 10)
+            .add_edge(1, 4, 10)
+            .add_edge(1, 3, 50)
+            .add_edge(2, 4, 33)
+            .add_edge(2, 3, 20)
+            .add_edge(3, 4, 5)
+            .add_edge(3, 5, 12)
+            .add_edge(4, 0, 12)
+            .add_edge(4, 5, 1)
+            .add_edge(5, 2, 22);
+        let graph = graph_builder.build();
+        for strategy in [
+            ShortestPathStrategy::FloydWarshall,
+            ShortestPathStrategy::Dijkstra,
+            ShortestPathStrategy::Johnson,
+        ] {
+            let path = CppSolver::with_strategy(graph.clone(), strategy)
+                .solve()
+                .unwrap();
+            assert_eq!(path.cost, 276);
+            assert!(path.to_string().ends_with("Cost: 276"));
+        }
+    }
+
+    #[test]
+    fn test_balancing_cost() {
+        let edges = [
+            (0, 2, 20.),
+            (0, 1, 10.),
+            (1, 4, 10.),
+            (1, 3, 50.),
+            (2, 4, 33.),
+            (2, 3, 20.),
+            (3, 4, 5.),
+            (3, 5, 12.),
+            (4, 0, 12.),
+            (4, 5, 1.),
+            (5, 2, 22.),
+        ];
+        let mut graph_builder = GraphBuilder::new();
+        for (from, to, weight) in edges {
+            graph_builder.add_edge(from, to, weight);
+        }
+        let path = CppSolver::new(graph_builder.build()).solve().unwrap();
+        let required_cost: f64 = edges.iter().map(|(_, _, weight)| weight).sum();
+        assert!(path.balancing_cost > 0.0);
+        assert!(path.cost_approx_eq(required_cost + path.balancing_cost, DEFAULT_COST_EPSILON));
+    }
+
+    #[test]
+    fn test_deterministic_path() {
+        let solve = || {
+            let mut graph_builder = GraphBuilder::new();
+            graph_builder
+                .add_labeled_edge("a", "c", 20.)
+                .add_labeled_edge("a", "b", 10.)
+                .add_labeled_edge("b", "e", 10.)
+                .add_labeled_edge("b", "d", 50.)
+                .add_labeled_edge("c", "e", 33.)
+                .add_labeled_edge("c", "d", 20.)
+                .add_labeled_edge("d", "e", 5.)
+                .add_labeled_edge("d", "f", 12.)
+                .add_labeled_edge("e", "a", 12.)
+                .add_labeled_edge("e", "f", 1.)
+                .add_labeled_edge("f", "c", 22.);
+            CppSolver::new(graph_builder.build())
+                .solve()
+                .unwrap()
+                .to_string()
+        };
+        let first = solve();
+        for _ in 0..10 {
+            assert_eq!(solve(), first);
+        }
+    }
+
+    #[test]
+    fn test_to_adjacency_list() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "c", 20.)
+            .add_labeled_edge("a", "b", 10.)
+            .add_labeled_edge("b", "e", 10.)
+            .add_labeled_edge("b", "d", 50.)
+            .add_labeled_edge("c", "e", 33.)
+            .add_labeled_edge("c", "d", 20.)
+            .add_labeled_edge("d", "e", 5.)
+            .add_labeled_edge("d", "f", 12.)
+            .add_labeled_edge("e", "a", 12.)
+            .add_labeled_edge("e", "f", 1.)
+            .add_labeled_edge("f", "c", 22.);
+        let adjacency_list = graph_builder.build().to_adjacency_list();
+        assert_eq!(adjacency_list.len(), 6);
+        let (label, neighbours) = &adjacency_list[3];
+        assert_eq!(label, "e");
+        assert_eq!(neighbours, &[("a".to_string(), 12.), ("f".to_string(), 1.)]);
+    }
+
+    #[test]
+    fn test_diameter_and_eccentricity() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 2, 20.)
+            .add_edge(0, 1, 10.)
+            .add_edge(1, 4, 10.)
+            .add_edge(1, 3, 50.)
+            .add_edge(2, 4, 33.)
+            .add_edge(2, 3, 20.)
+            .add_edge(3, 4, 5.)
+            .add_edge(3, 5, 12.)
+            .add_edge(4, 0, 12.)
+            .add_edge(4, 5, 1.)
+            .add_edge(5, 2, 22.);
+        let solver = CppSolver::new(graph_builder.build());
+        assert_eq!(solver.eccentricity(0), Some(40.));
+        assert_eq!(solver.eccentricity(6), None);
+        assert_eq!(solver.diameter(), Some(69.));
+
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder.add_edge(0, 1, 1.).add_edge(1, 2, 1.);
+        let solver = CppSolver::new(graph_builder.build());
+        assert_eq!(solver.eccentricity(0), Some(2.));
+        assert_eq!(solver.eccentricity(2), None);
+        assert_eq!(solver.diameter(), None);
+    }
+
+    #[test]
+    fn test_windy_edges() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_windy_edge(0, 1, 1., 10.)
+            .add_windy_edge(1, 2, 1., 10.)
+            .add_windy_edge(2, 0, 1., 10.)
+            .add_edge(2, 0, 100.);
+        let graph = graph_builder.build();
+        let path = CppSolver::new(graph.clone()).solve().unwrap();
+        // Going back from 0 to 2 the cheap way round costs 2, instead of 10 against the wind
+        assert!((path.balancing_cost - 2.).abs() <= DEFAULT_COST_EPSILON);
+        assert!(path.cost_approx_eq(135., DEFAULT_COST_EPSILON));
+        assert!(path.verify(&graph));
+    }
+
     #[test]
     fn test_label() {
         let mut graph_builder = GraphBuilder::new();
@@ -64,6 +537,173 @@ mod integration_tests {
         check_path(graph_builder, 276.);
     }
 
+    #[test]
+    fn test_graph_statistics() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "c", 20.)
+            .add_labeled_edge("a", "b", 10.)
+            .add_labeled_edge("b", "e", 10.)
+            .add_labeled_edge("b", "d", 50.)
+            .add_labeled_edge("c", "e", 33.)
+            .add_labeled_edge("c", "d", 20.)
+            .add_labeled_edge("d", "e", 5.)
+            .add_labeled_edge("d", "f", 12.)
+            .add_labeled_edge("e", "a", 12.)
+            .add_labeled_edge("e", "f", 1.)
+            .add_labeled_edge("f", "c", 22.);
+        let graph = graph_builder.build();
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 11);
+        // Nodes are indexed in the order their labels first appear: a, c, b, e, d, f
+        let out_degrees: Vec<_> = (0..6).map(|node| graph.out_degree(node)).collect();
+        let in_degrees: Vec<_> = (0..6).map(|node| graph.in_degree(node)).collect();
+        assert_eq!(out_degrees, [2, 2, 2, 2, 2, 1]);
+        assert_eq!(in_degrees, [1, 2, 1, 3, 2, 2]);
+    }
+
+    #[test]
+    fn test_label_lookup() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "c", 20.)
+            .add_labeled_edge("c", "b", 10.)
+            .add_labeled_edge("b", "a", 10.);
+        let graph = graph_builder.build();
+        assert_eq!(graph.index_of_label("a"), Some(0));
+        assert_eq!(graph.index_of_label("b"), Some(2));
+        assert_eq!(graph.index_of_label("z"), None);
+        assert_eq!(graph.label_of(1), Some("c"));
+        assert_eq!(graph.label_of(3), None);
+
+        // Indices on a solved route map back to their labels
+        let path = CppSolver::new(graph.clone()).solve().unwrap();
+        let labels: Vec<_> = path
+            .path
+            .iter()
+            .map(|&node| graph.label_of(node).unwrap())
+            .collect();
+        assert_eq!(labels, ["a", "c", "b", "a"]);
+    }
+
+    #[test]
+    fn test_shortest_distance_by_label() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "c", 20.)
+            .add_labeled_edge("a", "b", 10.)
+            .add_labeled_edge("b", "e", 10.)
+            .add_labeled_edge("b", "d", 50.)
+            .add_labeled_edge("c", "e", 33.)
+            .add_labeled_edge("c", "d", 20.)
+            .add_labeled_edge("d", "e", 5.)
+            .add_labeled_edge("d", "f", 12.)
+            .add_labeled_edge("e", "a", 12.)
+            .add_labeled_edge("e", "f", 1.)
+            .add_labeled_edge("f", "c", 22.);
+        let solver = CppSolver::new(graph_builder.build());
+        assert_eq!(solver.shortest_distance("a", "f"), Some(21.));
+        assert_eq!(solver.shortest_route("a", "f"), ["a", "b", "e", "f"]);
+        assert_eq!(solver.shortest_distance("f", "a"), Some(59.));
+        assert_eq!(solver.shortest_distance("a", "a"), Some(0.));
+        assert_eq!(solver.shortest_route("a", "a"), ["a"]);
+        assert_eq!(solver.shortest_distance("a", "z"), None);
+        assert!(solver.shortest_route("z", "a").is_empty());
+    }
+
+    #[test]
+    fn test_transpose() {
+        let edges = [
+            ("a", "c", 20.),
+            ("a", "b", 10.),
+            ("b", "e", 10.),
+            ("b", "d", 50.),
+            ("c", "e", 33.),
+            ("c", "d", 20.),
+            ("d", "e", 5.),
+            ("d", "f", 12.),
+            ("e", "a", 12.),
+            ("e", "f", 1.),
+            ("f", "c", 22.),
+        ];
+        let mut graph_builder = GraphBuilder::new();
+        let mut reversed_builder = GraphBuilder::new();
+        for (from, to, weight) in edges {
+            graph_builder.add_labeled_edge(from, to, weight);
+            reversed_builder.add_labeled_edge(to, from, weight);
+        }
+        let graph = graph_builder.build();
+        let transposed = graph.transpose();
+        let reversed = reversed_builder.build();
+        assert_eq!(transposed.weight_matrix(), graph.weight_matrix().t());
+        assert_eq!(transposed.node_labels(), graph.node_labels());
+        // The manually reversed graph numbers its nodes differently, so compare by label
+        assert!(transposed.diff(&reversed).is_empty());
+
+        let cost = CppSolver::new(graph).solve().unwrap().cost;
+        let transposed_path = CppSolver::new(transposed).solve().unwrap();
+        let reversed_cost = CppSolver::new(reversed).solve().unwrap().cost;
+        assert!(transposed_path.cost_approx_eq(reversed_cost, DEFAULT_COST_EPSILON));
+        assert!(transposed_path.cost_approx_eq(cost, DEFAULT_COST_EPSILON));
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "b", 1.)
+            .add_labeled_edge("b", "c", 1.)
+            .add_labeled_edge("c", "a", 1.)
+            .add_labeled_edge("c", "d", 5.)
+            .add_labeled_edge("d", "e", 1.)
+            .add_labeled_edge("e", "d", 1.);
+        let graph = graph_builder.build();
+        let components = vec![vec![0, 1, 2], vec![3, 4]];
+        assert_eq!(graph.strongly_connected_components(), components);
+        assert_eq!(
+            CppSolver::new(graph).solve().err(),
+            Some(SolveError::NotStronglyConnected(components))
+        );
+    }
+
+    #[test]
+    fn test_negative_weight() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "b", -2.)
+            .add_labeled_edge("b", "c", 3.)
+            .add_labeled_edge("c", "a", 1.)
+            .add_labeled_edge("a", "c", 4.);
+        check_path(graph_builder, 7.);
+    }
+
+    #[test]
+    fn test_solve_components() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "b", 1.)
+            .add_labeled_edge("b", "a", 2.)
+            .add_labeled_edge("b", "c", 10.)
+            .add_labeled_edge("c", "d", 3.)
+            .add_labeled_edge("d", "c", 4.)
+            .add_labeled_edge("d", "e", 10.);
+        let mut solver = CppSolver::new(graph_builder.build());
+        let paths: Vec<_> = solver
+            .solve_components()
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths[0].nodes(), ["a", "b", "a"]);
+        assert!(paths[0].cost_approx_eq(3., DEFAULT_COST_EPSILON));
+        assert_eq!(paths[1].nodes(), ["c", "d", "c"]);
+        assert_eq!(paths[1].path, [2, 3, 2]);
+        assert!(paths[1].cost_approx_eq(7., DEFAULT_COST_EPSILON));
+        // The isolated node "e" has nothing to walk
+        assert!(paths[2].path.is_empty());
+        assert!(paths[2].cost_approx_eq(0., DEFAULT_COST_EPSILON));
+    }
+
     #[test]
     fn test_odd_in_out_diff() {
         let mut graph_builder = GraphBuilder::new();
@@ -84,6 +724,136 @@ mod integration_tests {
         check_path(graph_builder, 419.);
     }
 
+    #[test]
+    fn test_estimate_bounds() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "c", 20.)
+            .add_labeled_edge("a", "b", 10.)
+            .add_labeled_edge("b", "e", 10.)
+            .add_labeled_edge("b", "d", 50.)
+            .add_labeled_edge("c", "e", 33.)
+            .add_labeled_edge("c", "d", 20.)
+            .add_labeled_edge("d", "e", 5.)
+            .add_labeled_edge("d", "f", 12.)
+            .add_labeled_edge("e", "a", 12.)
+            .add_labeled_edge("e", "f", 1.)
+            .add_labeled_edge("f", "c", 22.)
+            .add_labeled_edge("g", "c", 88.)
+            .add_labeled_edge("a", "g", 18.);
+        let mut solver = CppSolver::new(graph_builder.build());
+        let (lower, upper) = solver.estimate_bounds().unwrap();
+        let cost = solver.solve().unwrap().cost;
+        assert_eq!(lower, 301.);
+        assert!(lower <= cost && cost <= upper);
+        // A solved graph is balanced, so the bounds must ignore the edges balancing added.
+        assert_eq!(solver.estimate_bounds().unwrap(), (lower, upper));
+
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder.add_edge(0, 1, 1.).add_edge(1, 0, 2.);
+        let mut solver = CppSolver::new(graph_builder.build());
+        assert_eq!(solver.estimate_bounds().unwrap(), (3., 3.));
+    }
+
+    #[test]
+    fn test_balancing_routes() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "c", 20.)
+            .add_labeled_edge("a", "b", 10.)
+            .add_labeled_edge("b", "e", 10.)
+            .add_labeled_edge("b", "d", 50.)
+            .add_labeled_edge("c", "e", 33.)
+            .add_labeled_edge("c", "d", 20.)
+            .add_labeled_edge("d", "e", 5.)
+            .add_labeled_edge("d", "f", 12.)
+            .add_labeled_edge("e", "a", 12.)
+            .add_labeled_edge("e", "f", 1.)
+            .add_labeled_edge("f", "c", 22.)
+            .add_labeled_edge("g", "c", 88.)
+            .add_labeled_edge("a", "g", 18.);
+        let mut solver = CppSolver::new(graph_builder.build());
+        solver.solve().unwrap();
+        let expected = [
+            vec!["c", "d", "e", "a"],
+            vec!["e", "a"],
+            vec!["f", "c", "d", "e", "a", "b"],
+        ];
+        assert_eq!(solver.balancing_routes(), expected);
+        solver.reset();
+        assert!(solver.balancing_routes().is_empty());
+    }
+
+    #[test]
+    fn test_balancing_edges() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "c", 20.)
+            .add_labeled_edge("a", "b", 10.)
+            .add_labeled_edge("b", "e", 10.)
+            .add_labeled_edge("b", "d", 50.)
+            .add_labeled_edge("c", "e", 33.)
+            .add_labeled_edge("c", "d", 20.)
+            .add_labeled_edge("d", "e", 5.)
+            .add_labeled_edge("d", "f", 12.)
+            .add_labeled_edge("e", "a", 12.)
+            .add_labeled_edge("e", "f", 1.)
+            .add_labeled_edge("f", "c", 22.)
+            .add_labeled_edge("g", "c", 88.)
+            .add_labeled_edge("a", "g", 18.);
+        let mut solver = CppSolver::new(graph_builder.build());
+        let path = solver.solve().unwrap();
+        let expected = [
+            ("c", "d"),
+            ("d", "e"),
+            ("e", "a"),
+            ("e", "a"),
+            ("f", "c"),
+            ("c", "d"),
+            ("d", "e"),
+            ("e", "a"),
+            ("a", "b"),
+        ];
+        assert_eq!(
+            solver.balancing_edges(),
+            expected.map(|(from, to)| (from.to_string(), to.to_string()))
+        );
+        assert!((path.balancing_cost - 118.).abs() <= DEFAULT_COST_EPSILON);
+    }
+
+    #[test]
+    fn test_imbalance_report() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "c", 20.)
+            .add_labeled_edge("a", "b", 10.)
+            .add_labeled_edge("b", "e", 10.)
+            .add_labeled_edge("b", "d", 50.)
+            .add_labeled_edge("c", "e", 33.)
+            .add_labeled_edge("c", "d", 20.)
+            .add_labeled_edge("d", "e", 5.)
+            .add_labeled_edge("d", "f", 12.)
+            .add_labeled_edge("e", "a", 12.)
+            .add_labeled_edge("e", "f", 1.)
+            .add_labeled_edge("f", "c", 22.)
+            .add_labeled_edge("g", "c", 88.)
+            .add_labeled_edge("a", "g", 18.);
+        let report = graph_builder.build().imbalance_report();
+        let expected = [
+            ("a", 2),
+            ("c", -1),
+            ("b", 1),
+            ("e", -1),
+            ("d", 0),
+            ("f", -1),
+            ("g", 0),
+        ];
+        assert_eq!(
+            report,
+            expected.map(|(label, difference)| (label.to_string(), difference))
+        );
+    }
+
     #[test]
     fn test_non_one_in_out_diff() {
         let mut graph_builder = GraphBuilder::new();
@@ -103,4 +873,238 @@ mod integration_tests {
             .add_labeled_edge("b", "g", 67.);
         check_path(graph_builder, 414.);
     }
+
+    #[test]
+    fn test_greedy_matching() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "c", 20.)
+            .add_labeled_edge("a", "b", 10.)
+            .add_labeled_edge("b", "e", 10.)
+            .add_labeled_edge("b", "d", 50.)
+            .add_labeled_edge("c", "e", 33.)
+            .add_labeled_edge("c", "d", 20.)
+            .add_labeled_edge("d", "e", 5.)
+            .add_labeled_edge("d", "f", 12.)
+            .add_labeled_edge("e", "a", 12.)
+            .add_labeled_edge("e", "f", 1.)
+            .add_labeled_edge("f", "c", 22.)
+            .add_labeled_edge("g", "f", 2.)
+            .add_labeled_edge("b", "g", 67.);
+        let graph = graph_builder.build();
+        let config = SolverConfig {
+            matching: MatchingStrategy::Greedy,
+            ..SolverConfig::default()
+        };
+        let path = CppSolver::with_config(graph.clone(), config)
+            .solve()
+            .unwrap();
+        assert!(path.verify(&graph));
+        assert!(path.cost >= 414.);
+    }
+
+    #[test]
+    fn test_dijkstra_strategy() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_edge("a", "c", 20.)
+            .add_labeled_edge("a", "b", 10.)
+            .add_labeled_edge("b", "e", 10.)
+            .add_labeled_edge("b", "d", 50.)
+            .add_labeled_edge("c", "e", 33.)
+            .add_labeled_edge("c", "d", 20.)
+            .add_labeled_edge("d", "e", 5.)
+            .add_labeled_edge("d", "f", 12.)
+            .add_labeled_edge("e", "a", 12.)
+            .add_labeled_edge("e", "f", 1.)
+            .add_labeled_edge("f", "c", 22.)
+            .add_labeled_edge("g", "f", 2.)
+            .add_labeled_edge("b", "g", 67.);
+        let graph = graph_builder.build();
+        let mut solver = CppSolver::with_strategy(graph, ShortestPathStrategy::Dijkstra);
+        match solver.solve() {
+            Ok(path) => assert!(path.cost_approx_eq(414., DEFAULT_COST_EPSILON)),
+            Err(err) => panic!("No solution found: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_johnson_strategy() {
+        let build = || {
+            let mut graph_builder = GraphBuilder::new();
+            graph_builder
+                .add_labeled_edge("a", "c", 20.)
+                .add_labeled_edge("a", "b", 10.)
+                .add_labeled_edge("b", "e", 10.)
+                .add_labeled_edge("b", "d", 50.)
+                .add_labeled_edge("c", "e", 33.)
+                .add_labeled_edge("c", "d", -20.)
+                .add_labeled_edge("d", "e", 5.)
+                .add_labeled_edge("d", "f", 12.)
+                .add_labeled_edge("e", "a", 12.)
+                .add_labeled_edge("e", "f", 1.)
+                .add_labeled_edge("f", "c", 22.)
+                .add_labeled_edge("g", "f", 2.)
+                .add_labeled_edge("b", "g", 67.);
+            graph_builder.build()
+        };
+        let floyd_warshall = CppSolver::new(build()).solve().unwrap();
+        let johnson = CppSolver::with_strategy(build(), ShortestPathStrategy::Johnson)
+            .solve()
+            .unwrap();
+        assert!(johnson.cost_approx_eq(floyd_warshall.cost, DEFAULT_COST_EPSILON));
+    }
+
+    #[test]
+    fn test_undirected_odd_nodes() {
+        let mut graph_builder = GraphBuilder::new();
+        for (a, b, weight) in [
+            ("a", "b", 2.),
+            ("b", "c", 3.),
+            ("c", "d", 2.),
+            ("d", "a", 3.),
+            ("a", "c", 4.),
+        ] {
+            graph_builder.add_labeled_undirected_edge(a, b, weight);
+        }
+        check_undirected_path(graph_builder, 18.);
+    }
+
+    #[test]
+    fn test_undirected_triangle() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_undirected_edge("a", "b", 1.)
+            .add_labeled_undirected_edge("b", "c", 2.)
+            .add_labeled_undirected_edge("c", "a", 3.);
+        // Directed solving walks every street both ways, undirected solving only once.
+        check_path(graph_builder, 12.);
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_labeled_undirected_edge("a", "b", 1.)
+            .add_labeled_undirected_edge("b", "c", 2.)
+            .add_labeled_undirected_edge("c", "a", 3.);
+        check_undirected_path(graph_builder, 6.);
+    }
+
+    #[test]
+    fn test_undirected_config_koenigsberg() {
+        // The seven bridges of Königsberg: every land mass has an odd degree, and the cheapest
+        // pairing repeats two bridges.
+        let mut graph_builder = GraphBuilder::new();
+        for (a, b) in [
+            ("island", "north"),
+            ("island", "north"),
+            ("island", "south"),
+            ("island", "south"),
+            ("island", "east"),
+            ("north", "east"),
+            ("south", "east"),
+        ] {
+            graph_builder.add_labeled_undirected_edge(a, b, 1.);
+        }
+        let graph = graph_builder.build();
+        let config = SolverConfig {
+            undirected: true,
+            ..SolverConfig::default()
+        };
+        let mut solver = CppSolver::with_config(graph, config);
+        let path = solver.solve().unwrap();
+        assert!(path.cost_approx_eq(9., DEFAULT_COST_EPSILON));
+        assert!((path.balancing_cost - 2.).abs() <= DEFAULT_COST_EPSILON);
+        assert_eq!(path.path.len(), 10);
+        assert_eq!(path.path.front(), path.path.back());
+        assert!(solver
+            .solve_undirected()
+            .unwrap()
+            .cost_approx_eq(9., DEFAULT_COST_EPSILON));
+        let path = solver.solve_from(3).unwrap();
+        assert!(path.cost_approx_eq(9., DEFAULT_COST_EPSILON));
+        assert_eq!(path.path.front(), Some(&3));
+
+        // Directed solving walks every bridge both ways
+        solver.set_config(SolverConfig::default());
+        assert!(solver
+            .solve()
+            .unwrap()
+            .cost_approx_eq(14., DEFAULT_COST_EPSILON));
+    }
+
+    #[test]
+    fn test_zero_weight_edge() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 1, 1.)
+            .add_edge(1, 2, 0.)
+            .add_edge(2, 0, 1.)
+            .add_edge(2, 1, 3.);
+        let graph = graph_builder.build();
+        assert_eq!(graph.out_degree(1), 1);
+        assert_eq!(graph.in_degree(2), 1);
+        // Node 1 is balanced by walking the free edge to node 2 a second time.
+        let solution = CppSolver::new(graph.clone()).solve_detailed().unwrap();
+        assert!((solution.total_cost - 5.).abs() <= DEFAULT_COST_EPSILON);
+        assert!((solution.deadhead_cost - 0.).abs() <= DEFAULT_COST_EPSILON);
+        assert_eq!(solution.n_edges_traversed, 5);
+        let free_walks = solution
+            .path
+            .path
+            .iter()
+            .zip(solution.path.path.iter().skip(1))
+            .filter(|&(&from, &to)| (from, to) == (1, 2))
+            .count();
+        assert_eq!(free_walks, 2);
+        assert!(solution.path.verify(&graph));
+    }
+
+    #[test]
+    fn test_self_loop_with_other_edges() {
+        let mut graph_builder = GraphBuilder::new();
+        graph_builder
+            .add_edge(0, 1, 1.)
+            .add_edge(1, 2, 2.)
+            .add_edge(2, 0, 3.)
+            .add_edge(1, 1, 5.)
+            .add_edge(0, 2, 4.);
+        let graph = graph_builder.build();
+        assert_eq!(graph.out_degree(1), 2);
+        assert_eq!(graph.in_degree(1), 2);
+        assert!(graph
+            .imbalance_report()
+            .iter()
+            .all(|(label, difference)| { label != "1" || *difference == 0 }));
+        let path = CppSolver::new(graph.clone()).solve().unwrap();
+        // The loop is walked once, and balancing repeats 2 -> 0 but never the loop
+        assert!(path.cost_approx_eq(18., DEFAULT_COST_EPSILON));
+        assert!((path.balancing_cost - 3.).abs() <= DEFAULT_COST_EPSILON);
+        assert_eq!(path.edge_traversal_counts()[&(1, 1)], 1);
+        assert!(path.verify(&graph));
+    }
+
+    #[test]
+    fn test_scaled_weights_scale_cost() {
+        let mut graph =
+            GraphBuilder::from(vec![(0, 1, 1.), (1, 2, 5.), (2, 0, 2.), (2, 1, 3.)]).build();
+        let cost = CppSolver::new(graph.clone()).solve().unwrap().cost;
+        graph.scale_weights(2.5);
+        let path = CppSolver::new(graph).solve().unwrap();
+        assert!(path.cost_approx_eq(cost * 2.5, DEFAULT_COST_EPSILON));
+    }
+
+    #[cfg(feature = "rand")]
+    proptest::proptest! {
+        #![proptest_config(proptest::test_runner::Config::with_cases(300))]
+
+        #[test]
+        fn test_random_graph_cost_and_coverage(
+            n_nodes in 2usize..15,
+            edge_probability in 0.0..0.3,
+            seed: u64,
+        ) {
+            let graph = Graph::random_connected(n_nodes, edge_probability, 1.0..10.0, seed);
+            let path = CppSolver::new(graph.clone()).solve().unwrap();
+            proptest::prop_assert!(path.cost >= graph.required_edge_cost() - DEFAULT_COST_EPSILON);
+            proptest::prop_assert!(path.verify(&graph));
+        }
+    }
 }
@@ -0,0 +1,88 @@
+use pyo3::{
+    create_exception,
+    exceptions::{PyException, PyValueError},
+    prelude::*,
+    types::PyDict,
+};
+
+use crate::{CppSolver, Graph, GraphBuilder};
+
+create_exception!(
+    cpp_solver,
+    SolveError,
+    PyException,
+    "Raised when the graph has no postman route."
+);
+
+/// Builds a graph from Python, like `GraphBuilder`.
+#[pyclass(name = "GraphBuilder")]
+#[derive(Default)]
+pub struct PyGraphBuilder {
+    builder: GraphBuilder,
+}
+
+#[pymethods]
+impl PyGraphBuilder {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a directed edge between two numbered nodes, or raises `ValueError` if the weight is
+    /// NaN or infinite.
+    fn add_edge(&mut self, from: usize, to: usize, weight: f64) -> PyResult<()> {
+        self.builder
+            .try_add_edge(from, to, weight)
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Adds a directed edge between two labeled nodes, or raises `ValueError` if the weight is
+    /// NaN or infinite.
+    fn add_labeled_edge(&mut self, from: &str, to: &str, weight: f64) -> PyResult<()> {
+        self.builder
+            .try_add_labeled_edge(from, to, weight)
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        Ok(())
+    }
+
+    /// Builds the graph from the edges added so far, which are kept for later builds.
+    fn build(&self) -> PyGraph {
+        PyGraph {
+            graph: self.builder.clone().build(),
+        }
+    }
+}
+
+/// A built graph, ready to be solved.
+#[pyclass(name = "Graph", frozen)]
+pub struct PyGraph {
+    graph: Graph,
+}
+
+/// Solves the Chinese Postman Problem on a graph.
+///
+/// # Returns
+///
+/// A dict with the labels along the route as `path`, and its `cost`, or raises `SolveError` if
+/// the graph has no route.
+#[pyfunction]
+fn solve<'py>(py: Python<'py>, graph: &PyGraph) -> PyResult<Bound<'py, PyDict>> {
+    let path = CppSolver::new(graph.graph.clone())
+        .solve()
+        .map_err(|err| SolveError::new_err(err.to_string()))?;
+    let route = PyDict::new(py);
+    route.set_item("path", path.nodes())?;
+    route.set_item("cost", path.cost)?;
+    Ok(route)
+}
+
+/// The `cpp_solver` Python module.
+#[pymodule(name = "cpp_solver")]
+fn python_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyGraphBuilder>()?;
+    module.add_class::<PyGraph>()?;
+    module.add_function(wrap_pyfunction!(solve, module)?)?;
+    module.add("SolveError", module.py().get_type::<SolveError>())?;
+    Ok(())
+}
@@ -0,0 +1,113 @@
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{CppSolver, GraphBuilder};
+
+/// Solves the Chinese Postman Problem from JavaScript, e.g. in a browser demo.
+///
+/// Edges are added one at a time, then `solve` returns the route as an object.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct WasmSolver {
+    builder: GraphBuilder,
+}
+
+#[wasm_bindgen]
+impl WasmSolver {
+    /// Creates a solver without any edges.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a directed edge between two numbered nodes, or throws if the weight is not finite.
+    #[wasm_bindgen(js_name = addEdge)]
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: f64) -> Result<(), JsValue> {
+        self.builder
+            .try_add_edge(from, to, weight)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+        Ok(())
+    }
+
+    /// Adds a directed edge between two labeled nodes, or throws if the weight is not finite.
+    #[wasm_bindgen(js_name = addLabeledEdge)]
+    pub fn add_labeled_edge(&mut self, from: &str, to: &str, weight: f64) -> Result<(), JsValue> {
+        self.builder
+            .try_add_labeled_edge(from, to, weight)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+        Ok(())
+    }
+
+    /// Solves the problem on the edges added so far, which are kept for later solves.
+    ///
+    /// # Returns
+    ///
+    /// An object of the form `{route: [...labels], cost: ..., balancing_cost: ...}`, or throws
+    /// the reason the graph is not solvable.
+    pub fn solve(&self) -> Result<JsValue, JsValue> {
+        let path = CppSolver::new(self.builder.clone().build())
+            .solve()
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+        let route = Route {
+            route: path.nodes(),
+            cost: path.cost,
+            balancing_cost: path.balancing_cost,
+        };
+        Ok(serde_wasm_bindgen::to_value(&route)?)
+    }
+}
+
+/// The route returned to JavaScript by `WasmSolver::solve`.
+#[derive(Serialize)]
+struct Route {
+    route: Vec<String>,
+    cost: f64,
+    balancing_cost: f64,
+}
+
+/// Test that the standard graph is solved from JavaScript to the same cost as natively.
+#[cfg(all(test, target_arch = "wasm32"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+fn test_wasm_solver_standard_graph() {
+    let mut solver = WasmSolver::new();
+    for (from, to, weight) in [
+        ("a", "c", 20.),
+        ("a", "b", 10.),
+        ("b", "e", 10.),
+        ("b", "d", 50.),
+        ("c", "e", 33.),
+        ("c", "d", 20.),
+        ("d", "e", 5.),
+        ("d", "f", 12.),
+        ("e", "a", 12.),
+        ("e", "f", 1.),
+        ("f", "c", 22.),
+    ] {
+        solver.add_labeled_edge(from, to, weight).unwrap();
+    }
+    let route: serde_json::Value = serde_wasm_bindgen::from_value(solver.solve().unwrap()).unwrap();
+    let cost = route["cost"].as_f64().unwrap();
+    assert!((cost - 276.).abs() <= crate::DEFAULT_COST_EPSILON);
+    assert_eq!(route["route"][0], "a");
+}
+
+/// Test that a NaN or infinite weight throws instead of trapping the module.
+#[cfg(all(test, target_arch = "wasm32"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+fn test_wasm_solver_nan_weight() {
+    let mut solver = WasmSolver::new();
+    let error = solver.add_edge(0, 1, f64::NAN).unwrap_err();
+    assert_eq!(
+        error.as_string().unwrap(),
+        "the edge from 0 to 1 has a NaN weight"
+    );
+    assert!(solver.add_labeled_edge("a", "b", f64::NAN).is_err());
+    let error = solver.add_edge(0, 1, f64::INFINITY).unwrap_err();
+    assert_eq!(
+        error.as_string().unwrap(),
+        "the edge from 0 to 1 has an infinite weight"
+    );
+    solver.add_edge(0, 1, 1.0).unwrap();
+    solver.add_edge(1, 0, 2.0).unwrap();
+    assert!(solver.solve().is_ok());
+}
@@ -0,0 +1,158 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    iter::Sum,
+    ops::{Add, Sub},
+};
+
+/// Represents the numeric type of edge weights, e.g. `f64` distances or `i64` block counts.
+///
+/// Costs are summed in the weight type itself, so integer weights are solved exactly and
+/// printed without a fractional part.
+pub trait Weight:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Sum
+    + fmt::Debug
+    + fmt::Display
+    + Send
+    + Sync
+    + 'static
+{
+    /// Returns the weight of an empty path.
+    fn zero() -> Self;
+
+    /// Converts the weight to `f64`, for the matching steps that work on floats.
+    fn to_f64(self) -> f64;
+
+    /// Compares two weights with a total order, so they can be sorted and kept in a heap.
+    fn total_cmp(&self, other: &Self) -> Ordering;
+
+    /// Checks if the weight is not a number, which no edge may have.
+    fn is_nan(self) -> bool;
+
+    /// Checks if the weight is infinite, which no edge may have either, as a missing edge is
+    /// `None` rather than an infinite weight.
+    fn is_infinite(self) -> bool;
+
+    /// Adds two weights, or returns `None` if the sum doesn't fit in the type.
+    ///
+    /// Shortest paths treat such a sum as a path too long to take, rather than as a distance.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Sums the weights, compensating for rounding errors where the type has them.
+    fn compensated_sum<I: IntoIterator<Item = Self>>(weights: I) -> Self;
+
+    /// Sums the weights like `compensated_sum`, or returns `None` if the sum doesn't fit in the
+    /// type, like `checked_add`.
+    fn checked_sum<I: IntoIterator<Item = Self>>(weights: I) -> Option<Self>;
+}
+
+impl Weight for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn to_f64(self) -> f64 {
+        self
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        f64::total_cmp(self, other)
+    }
+
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+
+    fn is_infinite(self) -> bool {
+        f64::is_infinite(self)
+    }
+
+    /// The sum overflows when it rounds to infinity.
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(self + other).filter(|sum| sum.is_finite())
+    }
+
+    /// Neumaier's variant of Kahan summation, which keeps the low-order bits lost by each
+    /// addition and adds them back at the end.
+    ///
+    /// Once the sum is infinite there are no low-order bits left, and compensating would compute
+    /// `inf - inf`, so the infinite sum is returned as it is.
+    fn compensated_sum<I: IntoIterator<Item = Self>>(weights: I) -> Self {
+        let mut sum = 0.0;
+        let mut compensation = 0.0;
+        for weight in weights {
+            let total = sum + weight;
+            if total.is_finite() {
+                compensation += if sum.abs() >= weight.abs() {
+                    (sum - total) + weight
+                } else {
+                    (weight - total) + sum
+                };
+            }
+            sum = total;
+        }
+        if sum.is_finite() {
+            sum + compensation
+        } else {
+            sum
+        }
+    }
+
+    fn checked_sum<I: IntoIterator<Item = Self>>(weights: I) -> Option<Self> {
+        Some(Self::compensated_sum(weights)).filter(|sum| sum.is_finite())
+    }
+}
+
+impl Weight for i64 {
+    fn zero() -> Self {
+        0
+    }
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        self.cmp(other)
+    }
+
+    fn is_nan(self) -> bool {
+        false
+    }
+
+    fn is_infinite(self) -> bool {
+        false
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        i64::checked_add(self, other)
+    }
+
+    fn compensated_sum<I: IntoIterator<Item = Self>>(weights: I) -> Self {
+        weights.into_iter().sum()
+    }
+
+    fn checked_sum<I: IntoIterator<Item = Self>>(weights: I) -> Option<Self> {
+        weights.into_iter().try_fold(0, i64::checked_add)
+    }
+}
+
+/// Test that an infinite term or an overflowing sum stays infinite instead of turning into NaN.
+#[test]
+fn test_compensated_sum_infinite() {
+    assert_eq!(
+        f64::compensated_sum([1.0, f64::INFINITY, 2.0]),
+        f64::INFINITY
+    );
+    assert_eq!(
+        f64::compensated_sum([f64::NEG_INFINITY, 0.5]),
+        f64::NEG_INFINITY
+    );
+    assert_eq!(f64::compensated_sum([1e308, 1e308, 0.1]), f64::INFINITY);
+    assert!(f64::compensated_sum([f64::INFINITY, f64::NEG_INFINITY]).is_nan());
+    assert_eq!(f64::compensated_sum([0.1; 10]), 1.0);
+}
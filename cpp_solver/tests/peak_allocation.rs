@@ -0,0 +1,70 @@
+use cpp_solver::{CppSolver, GraphBuilder, DEFAULT_COST_EPSILON};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+// The allocator only replaces the one of this test binary, not the library's unit tests.
+struct PeakAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        PEAK.fetch_max(current, Ordering::SeqCst);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+fn peak_during<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(before, Ordering::SeqCst);
+    let result = f();
+    (result, PEAK.load(Ordering::SeqCst) - before)
+}
+
+#[test]
+fn test_solve_shares_weight_matrix() {
+    // A one-way ring with a few shortcuts, so balancing needs the all-pairs shortest paths
+    let n_nodes = 400;
+    let mut builder = GraphBuilder::new();
+    for node in 0..n_nodes {
+        builder.add_edge(node, (node + 1) % n_nodes, 1.0);
+    }
+    for node in 0..4 {
+        builder.add_edge(node * 100, node * 100 + 2, 1.5);
+    }
+    let graph = builder.build();
+    let matrix = n_nodes * n_nodes * std::mem::size_of::<Option<f64>>();
+    let next = n_nodes * n_nodes * std::mem::size_of::<Option<usize>>();
+
+    // Solving needs the distances and the next hops, but no copy of the weight matrix
+    let ((mut solver, first), peak) = peak_during(|| {
+        let mut solver = CppSolver::new(graph);
+        let path = solver.solve().unwrap();
+        (solver, path)
+    });
+    assert!(peak >= matrix + next, "Floyd-Warshall didn't run");
+    assert!(
+        peak < 2 * matrix + next,
+        "solving allocated {} bytes, enough for a copy of the {} byte weight matrix",
+        peak,
+        matrix
+    );
+
+    // Solving again reuses the shortest paths without allocating them again
+    let (second, peak) = peak_during(|| solver.solve().unwrap());
+    assert!(peak < matrix, "solving again allocated {} bytes", peak);
+    assert_eq!(second.path, first.path);
+    assert!(second.cost_approx_eq(first.cost, DEFAULT_COST_EPSILON));
+}
@@ -0,0 +1,65 @@
+"""Tests of the Python bindings, run with `maturin develop && pytest tests`."""
+
+import pytest
+
+import cpp_solver
+
+STANDARD_EDGES = [
+    ("a", "c", 20.0),
+    ("a", "b", 10.0),
+    ("b", "e", 10.0),
+    ("b", "d", 50.0),
+    ("c", "e", 33.0),
+    ("c", "d", 20.0),
+    ("d", "e", 5.0),
+    ("d", "f", 12.0),
+    ("e", "a", 12.0),
+    ("e", "f", 1.0),
+    ("f", "c", 22.0),
+]
+
+
+def test_solve_standard_graph():
+    builder = cpp_solver.GraphBuilder()
+    for from_, to, weight in STANDARD_EDGES:
+        builder.add_labeled_edge(from_, to, weight)
+    route = cpp_solver.solve(builder.build())
+    assert route["cost"] == 276.0
+    assert route["path"][0] == route["path"][-1] == "a"
+    walked = set(zip(route["path"], route["path"][1:]))
+    assert walked == {(from_, to) for from_, to, _ in STANDARD_EDGES}
+
+
+def test_solve_numbered_edges_twice():
+    builder = cpp_solver.GraphBuilder()
+    builder.add_edge(0, 1, 1.0)
+    builder.add_edge(1, 0, 2.0)
+    graph = builder.build()
+    assert cpp_solver.solve(graph) == {"path": ["0", "1", "0"], "cost": 3.0}
+    assert cpp_solver.solve(graph) == cpp_solver.solve(builder.build())
+
+
+def test_unsolvable_graph_raises():
+    builder = cpp_solver.GraphBuilder()
+    builder.add_edge(0, 1, 1.0)
+    with pytest.raises(cpp_solver.SolveError, match="not strongly connected"):
+        cpp_solver.solve(builder.build())
+
+
+def test_nan_weight_raises():
+    builder = cpp_solver.GraphBuilder()
+    with pytest.raises(ValueError, match="NaN weight"):
+        builder.add_edge(0, 1, float("nan"))
+    with pytest.raises(ValueError, match="NaN weight"):
+        builder.add_labeled_edge("a", "b", float("nan"))
+    builder.add_labeled_edge("a", "b", 1.0)
+    builder.add_labeled_edge("b", "a", 2.0)
+    assert cpp_solver.solve(builder.build()) == {"path": ["a", "b", "a"], "cost": 3.0}
+
+
+def test_infinite_weight_raises():
+    builder = cpp_solver.GraphBuilder()
+    with pytest.raises(ValueError, match="infinite weight"):
+        builder.add_edge(0, 1, float("inf"))
+    with pytest.raises(ValueError, match="infinite weight"):
+        builder.add_labeled_edge("a", "b", float("-inf"))
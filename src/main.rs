@@ -1,28 +1,134 @@
-use cpp_solver::CppSolver;
-use cpp_solver::GraphBuilder;
-
-fn main() {
-    let mut graph_builder = GraphBuilder::new();
-    graph_builder
-        .add_labeled_edge("a", "c", 20.)
-        .add_labeled_edge("a", "b", 10.)
-        .add_labeled_edge("b", "e", 10.)
-        .add_labeled_edge("b", "d", 50.)
-        .add_labeled_edge("c", "e", 33.)
-        .add_labeled_edge("c", "d", 20.)
-        .add_labeled_edge("d", "e", 5.)
-        .add_labeled_edge("d", "f", 12.)
-        .add_labeled_edge("e", "a", 12.)
-        .add_labeled_edge("e", "f", 1.)
-        .add_labeled_edge("f", "c", 22.)
-        .add_labeled_edge("g", "f", 2.)
-        .add_labeled_edge("b", "g", 67.);
-    let graph = graph_builder.build();
-    let mut solver = CppSolver::new(graph);
-    match solver.solve() {
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use cpp_solver::{CppSolver, GraphBuilder, ParseError, SolveError, SolverConfig};
+
+/// Solves the Chinese Postman Problem on a `from,to,weight` edge list.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// The CSV edge list to read, or standard input if omitted.
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// How the route is printed.
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+    /// The label of the node the route starts and ends at, or the first node if omitted.
+    #[arg(long)]
+    start: Option<String>,
+    /// The file to write the route to, or standard output if omitted.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+/// The output formats of the route.
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// One `from,to,weight` row per walked edge, in order.
+    Csv,
+    /// The labels along the route with its costs.
+    Json,
+    /// The graph in Graphviz DOT with the route highlighted.
+    Dot,
+}
+
+/// Represents the reasons the command fails, each with its own exit code.
+enum CliError {
+    Read(io::Error),
+    Parse(ParseError),
+    UnknownStart(String),
+    Solve(SolveError),
+    Write(io::Error),
+}
+
+impl CliError {
+    /// Returns the exit code of the error. Clap already uses 2 for invalid arguments.
+    ///
+    /// Input that can't be read exits with 3, also when reading fails partway through the CSV,
+    /// and malformed input with 6, which comes last to keep the older codes unchanged.
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Read(_) | CliError::Parse(ParseError::Io { .. }) => 3,
+            CliError::UnknownStart(_) | CliError::Solve(_) => 4,
+            CliError::Write(_) => 5,
+            CliError::Parse(_) => 6,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Read(err) => write!(f, "can't read the input: {}", err),
+            CliError::Parse(err) => write!(f, "invalid input: {}", err),
+            CliError::UnknownStart(label) => write!(f, "no node is labeled {:?}", label),
+            CliError::Solve(err) => write!(f, "no route found: {}", err),
+            CliError::Write(err) => write!(f, "can't write the route: {}", err),
+        }
+    }
+}
+
+/// Reads the graph, solves it and renders the route in the requested format.
+fn run(args: &Args) -> Result<String, CliError> {
+    let builder = match &args.input {
         Some(path) => {
-            println!("{}", path);
+            let file = File::open(path).map_err(CliError::Read)?;
+            GraphBuilder::from_csv_reader(BufReader::new(file))
+        }
+        None => GraphBuilder::from_csv_reader(io::stdin().lock()),
+    }
+    .map_err(CliError::Parse)?;
+    let graph = builder.build();
+
+    let start = match &args.start {
+        Some(label) => graph
+            .index_of_label(label)
+            .ok_or_else(|| CliError::UnknownStart(label.clone()))?,
+        None => 0,
+    };
+    let config = SolverConfig {
+        start,
+        ..SolverConfig::default()
+    };
+    let path = CppSolver::with_config(graph.clone(), config)
+        .solve()
+        .map_err(CliError::Solve)?;
+
+    Ok(match args.format {
+        Format::Csv => path
+            .iter_edges()
+            .map(|(from, to, weight)| format!("{},{},{}\n", from, to, weight))
+            .collect(),
+        Format::Json => {
+            let route = serde_json::json!({
+                "route": path.nodes(),
+                "cost": path.cost,
+                "balancing_cost": path.balancing_cost,
+            });
+            format!("{}\n", route)
+        }
+        Format::Dot => path.to_dot(&graph),
+    })
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let result = run(&args).and_then(|route| {
+        match &args.output {
+            Some(path) => std::fs::write(path, route),
+            None => io::stdout().write_all(route.as_bytes()),
+        }
+        .map_err(CliError::Write)
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::from(err.exit_code())
         }
-        None => panic!("No solution found"),
     }
 }
@@ -0,0 +1,79 @@
+use cpp_solver::DEFAULT_COST_EPSILON;
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_chinese_postman_problem"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn sample() -> String {
+    format!("{}/tests/standard.csv", env!("CARGO_MANIFEST_DIR"))
+}
+
+fn exit_code_for(name: &str, csv: &str) -> Option<i32> {
+    let input_path = std::env::temp_dir().join(format!("cpp_{}_{}.csv", name, std::process::id()));
+    std::fs::write(&input_path, csv).unwrap();
+    let output = run(&["--input", input_path.to_str().unwrap()]);
+    std::fs::remove_file(&input_path).unwrap();
+    output.status.code()
+}
+
+#[test]
+fn test_solve_csv() {
+    let output = run(&["--input", &sample(), "--start", "c"]);
+    assert!(output.status.success());
+    let route = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<_> = route.lines().collect();
+    assert!(rows[0].starts_with("c,"));
+    assert!(rows.last().unwrap().split(',').nth(1) == Some("c"));
+    let cost: f64 = rows
+        .iter()
+        .map(|row| row.split(',').nth(2).unwrap().parse::<f64>().unwrap())
+        .sum();
+    assert!((cost - 276.).abs() <= DEFAULT_COST_EPSILON);
+}
+
+#[test]
+fn test_solve_json() {
+    let output = run(&["--input", &sample(), "--format", "json"]);
+    assert!(output.status.success());
+    let route: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let cost = route["cost"].as_f64().unwrap();
+    assert!((cost - 276.).abs() <= DEFAULT_COST_EPSILON);
+    let balancing_cost = route["balancing_cost"].as_f64().unwrap();
+    assert!((balancing_cost - 81.).abs() <= DEFAULT_COST_EPSILON);
+    assert_eq!(route["route"][0], "a");
+}
+
+#[test]
+fn test_output_file_keeps_stdout_empty() {
+    let output_path = std::env::temp_dir().join(format!("cpp_route_{}.csv", std::process::id()));
+    let output = run(&[
+        "--input",
+        &sample(),
+        "--output",
+        output_path.to_str().unwrap(),
+    ]);
+    let route = std::fs::read_to_string(&output_path).unwrap();
+    std::fs::remove_file(&output_path).unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty(), "the solver wrote to stdout");
+    assert!(output.stderr.is_empty());
+    assert_eq!(route.lines().count(), 17);
+}
+
+#[test]
+fn test_exit_codes() {
+    let output = run(&["--input", "missing.csv"]);
+    assert_eq!(output.status.code(), Some(3));
+    let output = run(&["--input", &sample(), "--start", "z"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert_eq!(exit_code_for("one_way", "a,b,1\n"), Some(4));
+    assert_eq!(exit_code_for("overflow", "a,b,1e308\nb,a,1e308\n"), Some(4));
+    assert_eq!(exit_code_for("malformed", "a,b\n"), Some(6));
+    assert_eq!(exit_code_for("infinite", "a,b,inf\nb,a,1\n"), Some(6));
+    let output = run(&["--format", "xml"]);
+    assert_eq!(output.status.code(), Some(2));
+}